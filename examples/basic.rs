@@ -1,7 +1,6 @@
 extern crate assimp;
 
 use std::iter::repeat;
-use std::fs::File;
 
 use assimp::*;
 
@@ -27,8 +26,8 @@ fn main() {
         .transform_vertices(true)
         .gen_smooth_normals(false);
 
-    let mut io = io::CustomIO::callback(|path| {
-        File::open(path)
+    let mut io = io::CustomIO::callback(|path, mode| {
+        mode.options().open(path)
     });
 
     let scene: Scene = Scene::import_from("./examples/sphere.dae", None, &mut io).unwrap();
@@ -8,6 +8,8 @@ use ::ffi;
 use ::ffi::*;
 
 use traits::{Named, FromRaw};
+#[cfg(feature = "assimp5_1")]
+use node::Node;
 
 pub struct VertexWeight<'a> {
     raw: &'a ffi::AiVertexWeight
@@ -39,11 +41,13 @@ pub struct Bone<'a> {
 }
 
 impl<'a> FromRaw<'a, Bone<'a>> for Bone<'a> {
-    type Raw = ffi::AiBone;
+    type Raw = *const ffi::AiBone;
 
     #[inline(always)]
     fn from_raw(raw: &'a Self::Raw) -> Bone<'a> {
-        Bone { raw: raw }
+        Bone {
+            raw: unsafe { raw.as_ref().expect("Bone pointer provided by Assimp was NULL") },
+        }
     }
 }
 
@@ -72,6 +76,24 @@ impl<'a> Bone<'a> {
     pub fn offset(&self) -> &AiMatrix4x4 {
         &self.raw.offset_matrix
     }
+
+    /// Get the root node of the skeleton this bone belongs to,
+    /// if the scene was processed with `POPULATE_ARMATURE_DATA`
+    #[cfg(feature = "assimp5_1")]
+    pub fn armature(&self) -> Option<Node<'a>> {
+        if self.raw.armature.is_null() { None } else {
+            Some(Node::from_raw(&self.raw.armature))
+        }
+    }
+
+    /// Get the node of this bone in the scene graph,
+    /// if the scene was processed with `POPULATE_ARMATURE_DATA`
+    #[cfg(feature = "assimp5_1")]
+    pub fn node(&self) -> Option<Node<'a>> {
+        if self.raw.node.is_null() { None } else {
+            Some(Node::from_raw(&self.raw.node))
+        }
+    }
 }
 
 //////////////////
@@ -98,6 +120,119 @@ impl<'a> Face<'a> {
 
 //////////////////
 
+/// Subtracts `base` from `target` for every vertex, if both are present and have the same length
+fn vector_deltas(target: Option<&[AiVector3D]>, base: Option<&[AiVector3D]>) -> Option<Vec<AiVector3D>> {
+    match (target, base) {
+        (Some(target), Some(base)) if target.len() == base.len() => {
+            Some(target.iter().zip(base.iter()).map(|(t, b)| {
+                AiVector3D { x: t.x - b.x, y: t.y - b.y, z: t.z - b.z }
+            }).collect())
+        }
+        _ => None
+    }
+}
+
+/// A morph target, or blend shape, of a mesh
+///
+/// Morph targets replace the vertex data of their mesh, so they have the same number of vertices.
+pub struct MorphTarget<'a> {
+    raw: &'a ffi::AiAnimMesh
+}
+
+impl<'a> FromRaw<'a, MorphTarget<'a>> for MorphTarget<'a> {
+    type Raw = *const ffi::AiAnimMesh;
+
+    #[inline(always)]
+    fn from_raw(raw: &'a Self::Raw) -> MorphTarget<'a> {
+        MorphTarget {
+            raw: unsafe { raw.as_ref().expect("Anim mesh pointer provided by Assimp was NULL") },
+        }
+    }
+}
+
+impl<'a> MorphTarget<'a> {
+    /// Gets the name of the morph target, which requires the `assimp4_1` feature
+    #[inline]
+    pub fn name(&self) -> Option<Cow<'a, str>> {
+        #[cfg(feature = "assimp4_1")]
+        return Some(self.raw.name.to_string_lossy());
+
+        #[cfg(not(feature = "assimp4_1"))]
+        return None;
+    }
+
+    /// Gets the default weight of the morph target, which requires the `assimp4_1` feature
+    #[inline]
+    pub fn weight(&self) -> Option<f32> {
+        #[cfg(feature = "assimp4_1")]
+        return Some(self.raw.weight as f32);
+
+        #[cfg(not(feature = "assimp4_1"))]
+        return None;
+    }
+
+    #[inline(always)]
+    pub fn num_vertices(&self) -> usize {
+        self.raw.num_vertices as usize
+    }
+
+    impl_optional_slice!(vertices, vertices, num_vertices, AiVector3D);
+    impl_optional_slice!(normals, normals, num_vertices, AiVector3D);
+    impl_optional_slice!(tangents, tangents, num_vertices, AiVector3D);
+    impl_optional_slice!(bitangents, bitangents, num_vertices, AiVector3D);
+
+    /// Gets a specific vertex color set replacing the one of the mesh.
+    pub fn color_channel(&self, index: usize) -> Option<&'a [AiColor4D]> {
+        if index < ffi::MAX_NUMBER_OF_COLOR_SETS as usize {
+            if self.raw.colors[index].is_null() || self.raw.num_vertices == 0 { None } else {
+                Some(unsafe { slice::from_raw_parts(self.raw.colors[index], self.raw.num_vertices as usize) })
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Gets a specific UV(W) channel replacing the one of the mesh, which has as many dimensions as the mesh channel.
+    pub fn uv_channel(&self, index: usize) -> Option<&'a [AiVector3D]> {
+        if index < ffi::MAX_NUMBER_OF_TEXTURECOORDS as usize {
+            if self.raw.texcoords[index].is_null() || self.raw.num_vertices == 0 { None } else {
+                Some(unsafe { slice::from_raw_parts(self.raw.texcoords[index], self.raw.num_vertices as usize) })
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Computes the offset of every vertex position from the base mesh.
+    ///
+    /// Returns `None` if either has no positions or their vertex counts differ.
+    ///
+    /// This is NOT zero-cost, as it allocates and computes a delta for every vertex.
+    pub fn position_deltas(&self, base: &Mesh<'a>) -> Option<Vec<AiVector3D>> {
+        vector_deltas(self.vertices(), base.vertices())
+    }
+
+    /// Computes the offset of every vertex normal from the base mesh.
+    ///
+    /// Returns `None` if either has no normals or their vertex counts differ.
+    ///
+    /// This is NOT zero-cost, as it allocates and computes a delta for every vertex.
+    pub fn normal_deltas(&self, base: &Mesh<'a>) -> Option<Vec<AiVector3D>> {
+        vector_deltas(self.normals(), base.normals())
+    }
+
+    /// Computes the offset of every vertex tangent from the base mesh.
+    ///
+    /// Returns `None` if either has no tangents or their vertex counts differ.
+    ///
+    /// This is NOT zero-cost, as it allocates and computes a delta for every vertex.
+    pub fn tangent_deltas(&self, base: &Mesh<'a>) -> Option<Vec<AiVector3D>> {
+        vector_deltas(self.tangents(), base.tangents())
+    }
+}
+
+//////////////////
+
 enum_from_primitive! {
     #[repr(u32)]
     #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
@@ -141,6 +276,16 @@ impl<'a> Mesh<'a> {
         PrimitiveType::from_u32(self.raw.primitive_type as u32)
     }
 
+    #[inline(always)]
+    pub fn num_vertices(&self) -> usize {
+        self.raw.num_vertices as usize
+    }
+
+    #[inline(always)]
+    pub fn num_faces(&self) -> usize {
+        self.raw.num_faces as usize
+    }
+
     impl_optional_iterator!(faces, faces, num_faces, Face, {
         /// Returns an iterator to all the faces in the mesh
     });
@@ -169,6 +314,49 @@ impl<'a> Mesh<'a> {
         }
     }
 
+    #[inline(always)]
+    pub fn has_bones(&self) -> bool {
+        self.raw.num_bones > 0 && !self.raw.bones.is_null()
+    }
+
+    impl_optional_iterator!(bones, bones, num_bones, Bone, {
+        /// Returns an iterator to all the bones influencing the mesh
+    });
+
+    /// Get a specific bone of the mesh
+    pub fn bone(&self, index: usize) -> Option<Bone<'a>> {
+        if index >= self.raw.num_bones as usize || self.raw.bones.is_null() { None } else {
+            Some(Bone::from_raw(unsafe { &*self.raw.bones.offset(index as isize) }))
+        }
+    }
+
+    impl_optional_iterator!(morph_targets, anim_meshes, num_anim_meshes, MorphTarget, {
+        /// Returns an iterator to all the morph targets of the mesh
+    });
+
+    /// Get a specific morph target of the mesh
+    pub fn morph_target(&self, index: usize) -> Option<MorphTarget<'a>> {
+        if index >= self.raw.num_anim_meshes as usize || self.raw.anim_meshes.is_null() { None } else {
+            Some(MorphTarget::from_raw(unsafe { &*self.raw.anim_meshes.offset(index as isize) }))
+        }
+    }
+
+    /// Gets the number of active vertex color sets for the mesh. Meshes can have more than one.
+    pub fn color_channels(&self) -> usize {
+        self.raw.colors.iter().filter(|colors| !colors.is_null()).count()
+    }
+
+    /// Gets a specific vertex color set, with one RGBA color per vertex.
+    pub fn color_channel(&self, index: usize) -> Option<&'a [AiColor4D]> {
+        if index < ffi::MAX_NUMBER_OF_COLOR_SETS as usize {
+            if self.raw.colors[index].is_null() || self.raw.num_vertices == 0 { None } else {
+                Some(unsafe { slice::from_raw_parts(self.raw.colors[index], self.raw.num_vertices as usize) })
+            }
+        } else {
+            None
+        }
+    }
+
     /// Counts the number of indices for the mesh.
     ///
     /// This is NOT zero-cost, as it has to count all indices for all faces.
@@ -203,6 +391,14 @@ impl<'a> Mesh<'a> {
         }
     }
 
+    /// Get the axis-aligned bounding box of the mesh,
+    /// which is only computed if the scene was processed with `GEN_BOUNDING_BOXES`
+    #[cfg(feature = "assimp5_0")]
+    #[inline(always)]
+    pub fn aabb(&self) -> &'a AiAABB {
+        &self.raw.aabb
+    }
+
     /// Get the index of the material for this mesh
     #[inline(always)]
     pub fn material_index(&self) -> u32 {
@@ -61,4 +61,47 @@ impl<'a> Node<'a> {
             None
         }
     }
-}
\ No newline at end of file
+}
+
+/// Mutable view of a node, given out by `Scene::for_each_node_mut`
+pub struct NodeMut<'m> {
+    raw: &'m mut ffi::AiNode
+}
+
+impl<'m> NodeMut<'m> {
+    #[inline(always)]
+    pub(crate) fn from_raw_mut(raw: &'m mut ffi::AiNode) -> NodeMut<'m> {
+        NodeMut { raw: raw }
+    }
+
+    #[inline]
+    pub fn name(&self) -> Cow<str> {
+        self.raw.name.to_string_lossy()
+    }
+
+    /// Renames the node, truncating the name to `MAXLEN - 1` bytes.
+    ///
+    /// Animations refer to nodes by name, so renaming animated nodes detaches their channels.
+    #[inline]
+    pub fn set_name(&mut self, name: &str) {
+        self.raw.name = AiString::from(name);
+    }
+
+    /// Returns the transformation matrix of this node relative to its parent
+    #[inline(always)]
+    pub fn transformation(&self) -> &AiMatrix4x4 {
+        &self.raw.transformation
+    }
+
+    /// Get the transformation matrix of this node for modification
+    #[inline(always)]
+    pub fn transformation_mut(&mut self) -> &mut AiMatrix4x4 {
+        &mut self.raw.transformation
+    }
+
+    /// Returns the number of children of this node
+    #[inline]
+    pub fn num_children(&self) -> usize {
+        self.raw.num_children as usize
+    }
+}
@@ -2,11 +2,33 @@ use std::ffi::{CStr, NulError};
 use std::error::Error;
 use std::io;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::path::{Path, PathBuf};
 use std::str::Utf8Error;
 
 use trace_error::TraceResult;
 
 use ffi;
+use logging::ImportLog;
+use postprocess::{PostprocessEffect, EffectIssue};
+
+/// The file and postprocess effects of an import or postprocessing operation that failed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportContext {
+    pub path: PathBuf,
+    pub effects: Option<PostprocessEffect>,
+    /// Messages Assimp emitted before the operation failed, if they were captured
+    pub log: Option<ImportLog>,
+}
+
+impl ImportContext {
+    pub fn new<P: AsRef<Path>>(path: P, effects: Option<PostprocessEffect>) -> ImportContext {
+        ImportContext {
+            path: path.as_ref().to_path_buf(),
+            effects: effects,
+            log: None,
+        }
+    }
+}
 
 /// Represents any errors that can be encountered by the Assimp library
 #[derive(Debug)]
@@ -15,7 +37,24 @@ pub enum AiError {
     Utf8Error(Utf8Error),
     NulError(NulError),
     Internal(String),
-    InvalidScene
+    /// The file could not be opened
+    FileNotFound(ImportContext, String),
+    /// No importer supports the format of the file
+    UnsupportedFormat(ImportContext, String),
+    /// An importer failed to parse the file, possibly at a known line
+    ParseError { context: ImportContext, line: Option<u32>, message: String },
+    /// The imported data structure failed validation
+    ValidationFailed(ImportContext, String),
+    /// A postprocessing step failed
+    PostprocessFailed(ImportContext, String),
+    /// The import failed for a reason that could not be classified
+    ImportFailed(ImportContext, String),
+    /// The import was cancelled by the user
+    Cancelled(ImportContext),
+    /// The requested postprocess effects conflict with each other
+    InvalidEffects(Vec<EffectIssue>),
+    /// Import settings could not be parsed or contain unknown values
+    InvalidSettings(String),
 }
 
 /// Generic Result type with an `AiError` for the error type
@@ -38,19 +77,182 @@ impl AiError {
     }
 }
 
-/// This performs a `try!(AiError::check())` in place, with or without a condition
-//#[macro_export]
-macro_rules! check_assimp_errors {
-    () => { try!(AiError::check()) };
+/// Names importers prefix their errors with, like `"OBJ: "`
+const IMPORTER_PREFIXES: &'static [&'static str] = &[
+    "3ds", "3mf", "ac3d", "amf", "ase", "b3d", "blend", "blender", "bvh", "cob", "collada", "csm", "dxf",
+    "fbx", "gltf", "gltf2", "hmp", "ifc", "irr", "irrmesh", "lwo", "lws", "md2", "md3", "md5", "mdl",
+    "ms3d", "nff", "obj", "off", "ogre", "opengex", "ply", "q3bsp", "q3d", "raw", "sib", "smd", "stl",
+    "ter", "x", "x3d", "xfile", "xgl",
+];
 
-    ($condition:expr) => {
-        if $condition { try_rethrow!(AiError::check()) }
+/// Checks if an error message starts with the prefix of a known importer, like `"OBJ: "` or `"FBX-Parser: "`
+fn has_importer_prefix(message: &str) -> bool {
+    let prefix = match message.find(':') {
+        Some(index) => message[..index].trim(),
+        None => return false,
     };
+
+    // Some importers name their parts too, like "FBX-Tokenize"
+    let name = prefix.split('-').next().unwrap_or(prefix).to_lowercase();
+
+    IMPORTER_PREFIXES.contains(&name.as_str())
+}
+
+/// Finds a line number within a message, such as in `"unexpected token at line 12"` or `"model.obj:12: bad face"`
+fn find_line_number(message: &str) -> Option<u32> {
+    fn is_word_char(c: char) -> bool {
+        c.is_alphanumeric() || c == '_'
+    }
+
+    let lower = message.to_lowercase();
+
+    for (index, _) in lower.match_indices("line") {
+        let before = lower[..index].chars().next_back();
+        let rest = &lower[index + 4..];
+
+        // Only the whole word counts, not "spline" or "lines"
+        if before.map_or(false, is_word_char) || rest.chars().next().map_or(false, is_word_char) {
+            continue;
+        }
+
+        let rest = rest.trim_left_matches(|c: char| c == ' ' || c == ':' || c == '#');
+        let digits: String = rest.chars().take_while(|c| c.is_digit(10)).collect();
+
+        if rest[digits.len()..].chars().next().map_or(false, is_word_char) {
+            continue;
+        }
+
+        if let Ok(line) = digits.parse() {
+            return Some(line);
+        }
+    }
+
+    // Like "model.obj:12: bad face", where the number is enclosed by colons
+    let parts: Vec<&str> = message.split(':').collect();
+
+    if parts.len() >= 3 {
+        for part in &parts[1..parts.len() - 1] {
+            if !part.is_empty() && part.chars().all(|c| c.is_digit(10)) {
+                if let Ok(line) = part.parse() {
+                    return Some(line);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+impl AiError {
+    /// Classifies an error message emitted by Assimp when importing a file
+    pub fn from_import_message(context: ImportContext, message: &str) -> AiError {
+        let message = message.trim();
+        let lower = message.to_lowercase();
+
+        if lower.starts_with("unable to open file") || lower.contains("file not found") {
+            AiError::FileNotFound(context, message.to_string())
+        } else if lower.starts_with("no suitable reader found") || lower.contains("unsupported file format") {
+            AiError::UnsupportedFormat(context, message.to_string())
+        } else if lower.starts_with("validation failed") {
+            AiError::ValidationFailed(context, message.to_string())
+        } else if has_importer_prefix(message) {
+            AiError::ParseError { context: context, line: find_line_number(message), message: message.to_string() }
+        } else {
+            AiError::ImportFailed(context, message.to_string())
+        }
+    }
+
+    /// Classifies an error message emitted by Assimp when postprocessing a scene
+    pub fn from_postprocess_message(context: ImportContext, message: &str) -> AiError {
+        let message = message.trim();
+
+        if message.to_lowercase().starts_with("validation failed") {
+            AiError::ValidationFailed(context, message.to_string())
+        } else {
+            AiError::PostprocessFailed(context, message.to_string())
+        }
+    }
+
+    /// Classifies the reason for a failed import, taken from the log captured during it.
+    ///
+    /// Assimp's global error string is never used, since it may belong to an import on another thread.
+    pub fn import_failed(context: ImportContext, log: &ImportLog) -> AiError {
+        match log.last_error() {
+            Some(msg) => AiError::from_import_message(context, &msg.message),
+            None => AiError::ImportFailed(context, "Invalid Scene".to_string()),
+        }
+    }
+
+    /// Classifies the reason for failed postprocessing, taken from the log captured during it.
+    pub fn postprocess_failed(context: ImportContext, log: &ImportLog) -> AiError {
+        match log.last_error() {
+            Some(msg) => AiError::from_postprocess_message(context, &msg.message),
+            None => AiError::PostprocessFailed(context, "Invalid Scene".to_string()),
+        }
+    }
+
+    /// Gets the file and effects of the failed operation, if known
+    pub fn context(&self) -> Option<&ImportContext> {
+        match *self {
+            AiError::FileNotFound(ref context, _) |
+            AiError::UnsupportedFormat(ref context, _) |
+            AiError::ParseError { ref context, .. } |
+            AiError::ValidationFailed(ref context, _) |
+            AiError::PostprocessFailed(ref context, _) |
+            AiError::ImportFailed(ref context, _) |
+            AiError::Cancelled(ref context) => Some(context),
+            _ => None,
+        }
+    }
+
+    fn context_mut(&mut self) -> Option<&mut ImportContext> {
+        match *self {
+            AiError::FileNotFound(ref mut context, _) |
+            AiError::UnsupportedFormat(ref mut context, _) |
+            AiError::ParseError { ref mut context, .. } |
+            AiError::ValidationFailed(ref mut context, _) |
+            AiError::PostprocessFailed(ref mut context, _) |
+            AiError::ImportFailed(ref mut context, _) |
+            AiError::Cancelled(ref mut context) => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Attaches the messages Assimp emitted before the error occurred to its context.
+    ///
+    /// Errors without a context, like `AiError::Io`, are returned unchanged.
+    pub fn with_log(mut self, log: ImportLog) -> AiError {
+        if let Some(context) = self.context_mut() {
+            context.log = Some(log);
+        }
+
+        self
+    }
+
+    /// Gets the messages Assimp emitted before the error occurred, if they were captured
+    #[inline]
+    pub fn log(&self) -> Option<&ImportLog> {
+        self.context().and_then(|context| context.log.as_ref())
+    }
 }
 
 impl Display for AiError {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
-        write!(f, "{}", self.description())
+        match *self {
+            AiError::InvalidEffects(ref issues) => {
+                try!(write!(f, "{}", self.description()));
+
+                for issue in issues {
+                    try!(write!(f, "; {}", issue.message));
+                }
+
+                Ok(())
+            }
+            _ => match self.context() {
+                Some(context) => write!(f, "{}: {}", context.path.display(), self.description()),
+                None => write!(f, "{}", self.description()),
+            }
+        }
     }
 }
 
@@ -79,7 +281,15 @@ impl Error for AiError {
             AiError::Utf8Error(ref err) => err.description(),
             AiError::NulError(ref err) => err.description(),
             AiError::Internal(ref err) => err,
-            AiError::InvalidScene => "Invalid Scene"
+            AiError::FileNotFound(_, ref err) => err,
+            AiError::UnsupportedFormat(_, ref err) => err,
+            AiError::ParseError { ref message, .. } => message,
+            AiError::ValidationFailed(_, ref err) => err,
+            AiError::PostprocessFailed(_, ref err) => err,
+            AiError::ImportFailed(_, ref err) => err,
+            AiError::Cancelled(_) => "Import cancelled",
+            AiError::InvalidEffects(_) => "Conflicting postprocess effects",
+            AiError::InvalidSettings(ref err) => err,
         }
     }
 
@@ -91,4 +301,106 @@ impl Error for AiError {
             _ => None
         }
     }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use log::LogLevel;
+
+    use logging::LogMessage;
+
+    fn context() -> ImportContext {
+        ImportContext::new("model.obj", None)
+    }
+
+    #[test]
+    fn test_classify_import_message() {
+        match AiError::from_import_message(context(), "Unable to open file \"model.obj\".") {
+            AiError::FileNotFound(..) => (),
+            err => panic!("Unexpected {:?}", err),
+        }
+
+        match AiError::from_import_message(context(), "No suitable reader found for the file format of file \"model.xyz\".") {
+            AiError::UnsupportedFormat(..) => (),
+            err => panic!("Unexpected {:?}", err),
+        }
+
+        match AiError::from_import_message(context(), "Validation failed: aiMesh::mNumFaces is 0") {
+            AiError::ValidationFailed(..) => (),
+            err => panic!("Unexpected {:?}", err),
+        }
+
+        match AiError::from_import_message(context(), "PLY: Unexpected token at line 12") {
+            AiError::ParseError { line: Some(12), .. } => (),
+            err => panic!("Unexpected {:?}", err),
+        }
+
+        match AiError::from_import_message(context(), "OBJ: Invalid face indices") {
+            AiError::ParseError { line: None, .. } => (),
+            err => panic!("Unexpected {:?}", err),
+        }
+
+        match AiError::from_import_message(context(), "FBX-Parser: unexpected end of file, line 7") {
+            AiError::ParseError { line: Some(7), .. } => (),
+            err => panic!("Unexpected {:?}", err),
+        }
+
+        match AiError::from_import_message(context(), "Something went wrong") {
+            AiError::ImportFailed(..) => (),
+            err => panic!("Unexpected {:?}", err),
+        }
+
+        match AiError::from_import_message(context(), "Word: message") {
+            AiError::ImportFailed(..) => (),
+            err => panic!("Unexpected {:?}", err),
+        }
+
+        match AiError::from_import_message(context(), "Unexpected token at line 3") {
+            AiError::ImportFailed(..) => (),
+            err => panic!("Unexpected {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_attach_log() {
+        let log = ImportLog {
+            messages: vec![LogMessage { level: LogLevel::Error, message: "OBJ: Invalid face indices".to_string() }],
+        };
+
+        let err = AiError::import_failed(context(), &log).with_log(log.clone());
+
+        match err {
+            AiError::ParseError { ref context, line: None, .. } => assert_eq!(context.log.as_ref(), Some(&log)),
+            ref err => panic!("Unexpected {:?}", err),
+        }
+
+        assert_eq!(err.log(), Some(&log));
+        assert_eq!(AiError::Internal("failed".to_string()).with_log(log).log(), None);
+    }
+
+    #[test]
+    fn test_find_line_number() {
+        assert_eq!(find_line_number("Unexpected token at line 12"), Some(12));
+        assert_eq!(find_line_number("Line #4: bad face"), Some(4));
+        assert_eq!(find_line_number("model.obj:12: bad face"), Some(12));
+        assert_eq!(find_line_number("Invalid spline 3"), None);
+        assert_eq!(find_line_number("Postprocessing pipeline 2 failed"), None);
+        assert_eq!(find_line_number("Expected 3 lines 4 times"), None);
+        assert_eq!(find_line_number("Time: 12:30"), None);
+    }
+
+    #[test]
+    fn test_classify_postprocess_message() {
+        match AiError::from_postprocess_message(context(), "Validation failed: aiMesh::mNumFaces is 0") {
+            AiError::ValidationFailed(..) => (),
+            err => panic!("Unexpected {:?}", err),
+        }
+
+        match AiError::from_postprocess_message(context(), "Something went wrong") {
+            AiError::PostprocessFailed(..) => (),
+            err => panic!("Unexpected {:?}", err),
+        }
+    }
 }
\ No newline at end of file
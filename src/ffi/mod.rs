@@ -154,6 +154,13 @@ pub const IMPORTER_FLAG_SUPPORT_COMPRESSED: c_int = 0x4;
 pub const IMPORTER_FLAG_SUPPORT_LIMITED: c_int = 0x8;
 pub const IMPORTER_FLAG_EXPERIMENTAL: c_int = 0x10;
 
+pub const ASSIMP_CFLAGS_SHARED: c_uint = 0x1;
+pub const ASSIMP_CFLAGS_STLPORT: c_uint = 0x2;
+pub const ASSIMP_CFLAGS_DEBUG: c_uint = 0x4;
+pub const ASSIMP_CFLAGS_NOBOOST: c_uint = 0x8;
+pub const ASSIMP_CFLAGS_SINGLETHREADED: c_uint = 0x10;
+pub const ASSIMP_CFLAGS_DOUBLE_SUPPORT: c_uint = 0x20;
+
 pub const POSTPROCESS_CALC_TANGENT_SPACE: c_uint = 0x1;
 pub const POSTPROCESS_JOIN_IDENTICAL_VERTICES: c_uint = 0x2;
 pub const POSTPROCESS_MAKE_LEFT_HANDED: c_uint = 0x4;
@@ -180,6 +187,24 @@ pub const POSTPROCESS_FLIP_UVS: c_uint = 0x800000;
 pub const POSTPROCESS_FLIP_WINDING_ORDER: c_uint = 0x1000000;
 pub const POSTPROCESS_SPLIT_BY_BONE_COUNT: c_uint = 0x2000000;
 pub const POSTPROCESS_DEBONE: c_uint = 0x4000000;
+pub const POSTPROCESS_POPULATE_ARMATURE_DATA: c_uint = 0x4000;
+pub const POSTPROCESS_GLOBAL_SCALE: c_uint = 0x8000000;
+pub const POSTPROCESS_EMBED_TEXTURES: c_uint = 0x10000000;
+pub const POSTPROCESS_FORCE_GEN_NORMALS: c_uint = 0x20000000;
+pub const POSTPROCESS_DROP_NORMALS: c_uint = 0x40000000;
+pub const POSTPROCESS_GEN_BOUNDING_BOXES: c_uint = 0x80000000;
+
+pub const COMPONENT_NORMALS: c_uint = 0x2;
+pub const COMPONENT_TANGENTS_AND_BITANGENTS: c_uint = 0x4;
+pub const COMPONENT_COLORS: c_uint = 0x8;
+pub const COMPONENT_TEXCOORDS: c_uint = 0x10;
+pub const COMPONENT_BONEWEIGHTS: c_uint = 0x20;
+pub const COMPONENT_ANIMATIONS: c_uint = 0x40;
+pub const COMPONENT_TEXTURES: c_uint = 0x80;
+pub const COMPONENT_LIGHTS: c_uint = 0x100;
+pub const COMPONENT_CAMERAS: c_uint = 0x200;
+pub const COMPONENT_MESHES: c_uint = 0x400;
+pub const COMPONENT_MATERIALS: c_uint = 0x800;
 
 pub const ANIM_BEHAVIOR_DEFAULT: c_uint = 0x0;
 pub const ANIM_BEHAVIOR_CONSTANT: c_uint = 0x1;
@@ -188,6 +213,10 @@ pub const ANIM_BEHAVIOR_REPEAT: c_uint = 0x3;
 
 #[repr(C)]
 pub struct AiString {
+    /// Length in bytes without the terminating nul, which Assimp 5.0 and newer store as `ai_uint32`
+    #[cfg(feature = "assimp5_0")]
+    pub length: u32,
+    #[cfg(not(feature = "assimp5_0"))]
     pub length: size_t,
     pub data: [c_uchar; MAXLEN as usize],
 }
@@ -202,9 +231,23 @@ impl AiString {
     }
 }
 
+impl<'a> From<&'a str> for AiString {
+    /// Copies the string, truncating it to `MAXLEN - 1` bytes if necessary
+    fn from(s: &'a str) -> AiString {
+        let bytes = s.as_bytes();
+        let length = if bytes.len() < MAXLEN { bytes.len() } else { MAXLEN - 1 };
+
+        let mut string = AiString { length: length as _, data: [0; MAXLEN as usize] };
+
+        string.data[..length].copy_from_slice(&bytes[..length]);
+
+        string
+    }
+}
+
 impl From<AiString> for String {
     fn from(s: AiString) -> String {
-        String::from_utf8_lossy(&s.data[0..s.length]).into()
+        String::from_utf8_lossy(&s.data[0..s.length as usize]).into()
     }
 }
 
@@ -446,12 +489,24 @@ pub struct AiVertexWeight {
 pub struct AiBone {
     pub name: AiString,
     pub num_weights: c_uint,
+    /// Root node of the skeleton, set by `POPULATE_ARMATURE_DATA` in Assimp 5.1 and newer.
+    ///
+    /// Assimp built with `ASSIMP_BUILD_NO_ARMATUREPOPULATE_PROCESS` leaves out this field and `node`,
+    /// so the `assimp5_1` feature must not be used with it.
+    #[cfg(feature = "assimp5_1")]
+    pub armature: *const AiNode,
+    /// Node of the bone itself, set by `POPULATE_ARMATURE_DATA` in Assimp 5.1 and newer
+    #[cfg(feature = "assimp5_1")]
+    pub node: *const AiNode,
     pub weights: *const AiVertexWeight,
     pub offset_matrix: AiMatrix4x4,
 }
 
 #[repr(C)]
 pub struct AiAnimMesh {
+    /// Name of the morph target, in Assimp 4.1 and newer
+    #[cfg(feature = "assimp4_1")]
+    pub name: AiString,
     pub vertices: *const AiVector3D,
     pub normals: *const AiVector3D,
     pub tangents: *const AiVector3D,
@@ -459,6 +514,9 @@ pub struct AiAnimMesh {
     pub colors: [*const AiColor4D; MAX_NUMBER_OF_COLOR_SETS as usize],
     pub texcoords: [*const AiVector3D; MAX_NUMBER_OF_TEXTURECOORDS as usize],
     pub num_vertices: c_uint,
+    /// Default weight of the morph target, in Assimp 4.1 and newer
+    #[cfg(feature = "assimp4_1")]
+    pub weight: c_float,
 }
 
 #[repr(C)]
@@ -478,9 +536,21 @@ pub struct AiMesh {
     pub bones: *const *const AiBone,
     pub material_index: c_uint,
     pub name: AiString,
-    //Not in use yet by Assimp
-    num_anim_meshes: c_uint,
-    anim_meshes: *const *const AiAnimMesh,
+    pub num_anim_meshes: c_uint,
+    pub anim_meshes: *const *const AiAnimMesh,
+    /// Morphing method of the anim meshes, in Assimp 5.0 and newer
+    #[cfg(feature = "assimp5_0")]
+    pub method: c_uint,
+    /// Set by `GEN_BOUNDING_BOXES` in Assimp 5.0 and newer
+    #[cfg(feature = "assimp5_0")]
+    pub aabb: AiAABB,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct AiAABB {
+    pub min: AiVector3D,
+    pub max: AiVector3D,
 }
 
 #[repr(C)]
@@ -539,6 +609,20 @@ pub struct AiScene {
     _private: *const c_void,
 }
 
+#[repr(C)]
+pub struct AiImporterDesc {
+    pub name: *const c_char,
+    pub author: *const c_char,
+    pub maintainer: *const c_char,
+    pub comments: *const c_char,
+    pub flags: c_uint,
+    pub min_major: c_uint,
+    pub min_minor: c_uint,
+    pub max_major: c_uint,
+    pub max_minor: c_uint,
+    pub file_extensions: *const c_char,
+}
+
 pub type AiFileReadProc = extern "C" fn(file: *mut AiFile, buffer: *mut c_char, size: size_t, count: size_t) -> size_t;
 
 pub type AiFileWriteProc = extern "C" fn(file: *mut AiFile, buffer: *const c_char, size: size_t, count: size_t) -> size_t;
@@ -555,6 +639,18 @@ pub type AiFileCloseProc = extern "C" fn(file_io: *mut AiFileIO, file: *mut AiFi
 
 pub type AiUserData = *mut c_char;
 
+/// Opaque property store used to configure imports
+pub enum AiPropertyStore {}
+
+pub type AiLogStreamCallback = extern "C" fn(message: *const c_char, user: *mut c_char);
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct AiLogStream {
+    pub callback: AiLogStreamCallback,
+    pub user: *mut c_char,
+}
+
 #[repr(C)]
 pub struct AiFileIO {
     pub open: AiFileOpenProc,
@@ -576,6 +672,10 @@ pub struct AiFile {
 extern "C" {
     pub fn aiApplyPostProcessing(scene: *const AiScene, flags: c_uint) -> *const AiScene;
 
+    pub fn aiAttachLogStream(stream: *const AiLogStream);
+
+    pub fn aiDetachLogStream(stream: *const AiLogStream) -> c_int;
+
     pub fn aiDetachAllLogStreams();
 
     pub fn aiEnableVerboseLogging(enable: c_int);
@@ -584,13 +684,137 @@ extern "C" {
 
     pub fn aiGetExtensionList(out: *mut AiString);
 
+    pub fn aiGetImportFormatCount() -> size_t;
+
+    pub fn aiGetImportFormatDescription(index: size_t) -> *const AiImporterDesc;
+
+    pub fn aiGetVersionMajor() -> c_uint;
+
+    pub fn aiGetVersionMinor() -> c_uint;
+
+    pub fn aiGetVersionRevision() -> c_uint;
+
+    pub fn aiGetCompileFlags() -> c_uint;
+
+    pub fn aiGetBranchName() -> *const c_char;
+
+    pub fn aiGetLegalString() -> *const c_char;
+
     pub fn aiImportFile(path: *const c_char, flags: c_uint) -> *const AiScene;
 
     pub fn aiImportFileEx(path: *const c_char, flags: c_uint, file_io: *mut AiFileIO) -> *const AiScene;
 
+    pub fn aiImportFileExWithProperties(path: *const c_char, flags: c_uint, file_io: *mut AiFileIO, props: *const AiPropertyStore) -> *const AiScene;
+
     pub fn aiImportFileFromMemory(buffer: *const c_char, length: c_uint, flags: c_uint, path_hint: *const c_char) -> *const AiScene;
 
     pub fn aiIsExtensionSupported(extension: *const c_char) -> c_int;
 
     pub fn aiReleaseImport(scene: *const AiScene);
-}
\ No newline at end of file
+
+    pub fn aiCreatePropertyStore() -> *mut AiPropertyStore;
+
+    pub fn aiReleasePropertyStore(store: *mut AiPropertyStore);
+
+    pub fn aiSetImportPropertyInteger(store: *mut AiPropertyStore, name: *const c_char, value: c_int);
+
+    pub fn aiSetImportPropertyFloat(store: *mut AiPropertyStore, name: *const c_char, value: AiReal);
+
+    pub fn aiSetImportPropertyString(store: *mut AiPropertyStore, name: *const c_char, value: *const AiString);
+
+    pub fn aiSetImportPropertyMatrix(store: *mut AiPropertyStore, name: *const c_char, value: *const AiMatrix4x4);
+}
+
+#[cfg(all(test, target_pointer_width = "64", not(feature = "double_precision")))]
+mod test {
+    use std::mem;
+
+    use super::*;
+
+    macro_rules! offset_of {
+        ($ty:ty, $field:ident) => {{
+            let value: $ty = unsafe { mem::zeroed() };
+
+            &value.$field as *const _ as usize - &value as *const _ as usize
+        }}
+    }
+
+    // The expected values are `sizeof` and `offsetof` of the structures as declared in the C headers
+    // of Assimp 4.0, 4.1, 5.0 and 5.1, compiled for x86_64.
+
+    #[test]
+    fn test_string_layout() {
+        #[cfg(feature = "assimp5_0")]
+        assert_eq!(mem::size_of::<AiString>(), 1028);
+
+        #[cfg(not(feature = "assimp5_0"))]
+        assert_eq!(mem::size_of::<AiString>(), 1032);
+    }
+
+    #[test]
+    fn test_bone_layout() {
+        #[cfg(feature = "assimp5_1")]
+        {
+            assert_eq!(offset_of!(AiBone, num_weights), 1028);
+            assert_eq!(offset_of!(AiBone, armature), 1032);
+            assert_eq!(offset_of!(AiBone, node), 1040);
+            assert_eq!(offset_of!(AiBone, weights), 1048);
+            assert_eq!(offset_of!(AiBone, offset_matrix), 1056);
+            assert_eq!(mem::size_of::<AiBone>(), 1120);
+        }
+
+        #[cfg(all(feature = "assimp5_0", not(feature = "assimp5_1")))]
+        {
+            assert_eq!(offset_of!(AiBone, num_weights), 1028);
+            assert_eq!(offset_of!(AiBone, weights), 1032);
+            assert_eq!(offset_of!(AiBone, offset_matrix), 1040);
+            assert_eq!(mem::size_of::<AiBone>(), 1104);
+        }
+
+        #[cfg(not(feature = "assimp5_0"))]
+        {
+            assert_eq!(offset_of!(AiBone, num_weights), 1032);
+            assert_eq!(offset_of!(AiBone, weights), 1040);
+            assert_eq!(offset_of!(AiBone, offset_matrix), 1048);
+            assert_eq!(mem::size_of::<AiBone>(), 1112);
+        }
+    }
+
+    #[test]
+    fn test_mesh_layout() {
+        // Meshes are only read through pointers, so fields appended by later versions don't matter
+        #[cfg(feature = "assimp5_0")]
+        {
+            assert_eq!(offset_of!(AiMesh, name), 236);
+            assert_eq!(offset_of!(AiMesh, num_anim_meshes), 1264);
+            assert_eq!(offset_of!(AiMesh, anim_meshes), 1272);
+            assert_eq!(offset_of!(AiMesh, method), 1280);
+            assert_eq!(offset_of!(AiMesh, aabb), 1284);
+        }
+
+        #[cfg(not(feature = "assimp5_0"))]
+        {
+            assert_eq!(offset_of!(AiMesh, name), 240);
+            assert_eq!(offset_of!(AiMesh, num_anim_meshes), 1272);
+            assert_eq!(offset_of!(AiMesh, anim_meshes), 1280);
+        }
+    }
+
+    #[test]
+    fn test_anim_mesh_layout() {
+        #[cfg(feature = "assimp4_1")]
+        {
+            assert_eq!(offset_of!(AiAnimMesh, vertices), 1032);
+            assert_eq!(offset_of!(AiAnimMesh, num_vertices), 1192);
+            assert_eq!(offset_of!(AiAnimMesh, weight), 1196);
+            assert_eq!(mem::size_of::<AiAnimMesh>(), 1200);
+        }
+
+        #[cfg(not(feature = "assimp4_1"))]
+        {
+            assert_eq!(offset_of!(AiAnimMesh, vertices), 0);
+            assert_eq!(offset_of!(AiAnimMesh, num_vertices), 160);
+            assert_eq!(mem::size_of::<AiAnimMesh>(), 168);
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Detects model formats from file contents, regardless of file names
+//!
+//! Binary formats are recognized by their magic bytes, while text formats fall back to
+//! heuristics on their first few keywords, as reported by `Confidence`.
+
+use std::cmp;
+use std::io::prelude::*;
+use std::io;
+
+/// Number of bytes `detect_reader` reads to detect a format
+pub const HEADER_LENGTH: usize = 4096;
+
+/// Model formats which can be detected
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    Fbx,
+    Gltf,
+    Stl,
+    Ply,
+    Collada,
+    Obj,
+    ThreeDs,
+    Blender,
+}
+
+/// Whether a file is stored as binary data or text
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    Binary,
+    Ascii,
+}
+
+/// How the format was detected
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Confidence {
+    /// Detected from keywords or structure, which other files may share
+    Heuristic,
+    /// Detected from magic bytes or a signature unique to the format
+    Magic,
+}
+
+/// Result of detecting the format of a file
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FormatGuess {
+    pub format: Format,
+    pub encoding: Encoding,
+    pub confidence: Confidence,
+}
+
+impl FormatGuess {
+    #[inline]
+    fn new(format: Format, encoding: Encoding, confidence: Confidence) -> FormatGuess {
+        FormatGuess { format: format, encoding: encoding, confidence: confidence }
+    }
+
+    /// The usual file extension for the detected format, without a leading dot
+    pub fn extension(&self) -> &'static str {
+        match self.format {
+            Format::Fbx => "fbx",
+            Format::Gltf => if self.encoding == Encoding::Binary { "glb" } else { "gltf" },
+            Format::Stl => "stl",
+            Format::Ply => "ply",
+            Format::Collada => "dae",
+            Format::Obj => "obj",
+            Format::ThreeDs => "3ds",
+            Format::Blender => "blend",
+        }
+    }
+
+    /// Checks if Assimp supports the extension of the detected format
+    #[inline]
+    pub fn is_supported(&self) -> bool {
+        super::is_extension_supported(self.extension())
+    }
+}
+
+/// Detect the format of an entire file.
+///
+/// Only the first `HEADER_LENGTH` bytes are inspected, like with `detect_reader`,
+/// while the total size is used to recognize binary STL files.
+#[inline]
+pub fn detect(bytes: &[u8]) -> Option<FormatGuess> {
+    detect_header(&bytes[..cmp::min(bytes.len(), HEADER_LENGTH)], Some(bytes.len() as u64))
+}
+
+/// Detect the format of a file from the first `HEADER_LENGTH` bytes of the reader
+///
+/// Binary STL files are only recognized by their size, which is unknown here,
+/// so they can only be detected heuristically.
+pub fn detect_reader<R: Read>(reader: R) -> io::Result<Option<FormatGuess>> {
+    let mut header = Vec::with_capacity(HEADER_LENGTH);
+
+    try!(reader.take(HEADER_LENGTH as u64).read_to_end(&mut header));
+
+    Ok(detect_header(&header, None))
+}
+
+fn detect_header(bytes: &[u8], total: Option<u64>) -> Option<FormatGuess> {
+    use self::Confidence::*;
+    use self::Encoding::*;
+
+    if bytes.starts_with(b"Kaydara FBX Binary") {
+        return Some(FormatGuess::new(Format::Fbx, Binary, Magic));
+    }
+
+    if bytes.starts_with(b"glTF") && bytes.len() >= 8 && (read_u32(&bytes[4..]) == 1 || read_u32(&bytes[4..]) == 2) {
+        return Some(FormatGuess::new(Format::Gltf, Binary, Magic));
+    }
+
+    if bytes.starts_with(b"BLENDER") {
+        return Some(FormatGuess::new(Format::Blender, Binary, Magic));
+    }
+
+    if bytes.starts_with(b"ply\n") || bytes.starts_with(b"ply\r\n") {
+        let encoding = if contains(bytes, b"format binary") { Binary } else { Ascii };
+
+        return Some(FormatGuess::new(Format::Ply, encoding, Magic));
+    }
+
+    if is_3ds(bytes, total) {
+        return Some(FormatGuess::new(Format::ThreeDs, Binary, Magic));
+    }
+
+    let text = skip_whitespace(if bytes.starts_with(b"\xEF\xBB\xBF") { &bytes[3..] } else { bytes });
+
+    if !bytes.contains(&0) {
+        if text.starts_with(b"; FBX") {
+            return Some(FormatGuess::new(Format::Fbx, Ascii, Magic));
+        }
+
+        if contains(text, b"FBXHeaderExtension") {
+            return Some(FormatGuess::new(Format::Fbx, Ascii, Heuristic));
+        }
+
+        if text.starts_with(b"<") && contains(text, b"<COLLADA") {
+            return Some(FormatGuess::new(Format::Collada, Ascii, Magic));
+        }
+
+        if text.starts_with(b"{") && contains(text, b"\"asset\"") {
+            return Some(FormatGuess::new(Format::Gltf, Ascii, Heuristic));
+        }
+
+        if text.starts_with(b"solid") {
+            // Binary STL headers may start with "solid" too, so look for the first facet
+            let confidence = if contains(text, b"facet") || contains(text, b"endsolid") { Magic } else { Heuristic };
+
+            return Some(FormatGuess::new(Format::Stl, Ascii, confidence));
+        }
+
+        if is_obj(text) {
+            return Some(FormatGuess::new(Format::Obj, Ascii, Heuristic));
+        }
+    }
+
+    if bytes.len() >= 84 {
+        let count = read_u32(&bytes[80..]) as u64;
+
+        match total {
+            Some(total) if total == 84 + 50 * count => {
+                return Some(FormatGuess::new(Format::Stl, Binary, Magic));
+            }
+            None if count > 0 && bytes.contains(&0) => {
+                return Some(FormatGuess::new(Format::Stl, Binary, Heuristic));
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+#[inline]
+fn read_u16(bytes: &[u8]) -> u16 {
+    bytes[0] as u16 | (bytes[1] as u16) << 8
+}
+
+#[inline]
+fn read_u32(bytes: &[u8]) -> u32 {
+    read_u16(bytes) as u32 | (read_u16(&bytes[2..]) as u32) << 16
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+fn skip_whitespace(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|b| !(*b as char).is_whitespace()).unwrap_or(bytes.len());
+
+    &bytes[start..]
+}
+
+/// 3DS files start with a main chunk spanning the whole file, followed by a version or editor chunk
+fn is_3ds(bytes: &[u8], total: Option<u64>) -> bool {
+    if bytes.len() < 8 || read_u16(bytes) != 0x4D4D {
+        return false;
+    }
+
+    let length = read_u32(&bytes[2..]) as u64;
+
+    if length < 6 || total.map_or(false, |total| length != total) {
+        return false;
+    }
+
+    let next = read_u16(&bytes[6..]);
+
+    next == 0x0002 || next == 0x3D3D
+}
+
+/// OBJ files are plain lines of keywords, so look for any line starting with a common one
+fn is_obj(text: &[u8]) -> bool {
+    const KEYWORDS: &'static [&'static [u8]] = &[b"v ", b"vt ", b"vn ", b"f ", b"o ", b"g ", b"mtllib ", b"usemtl "];
+
+    text.split(|b| *b == b'\n')
+        .map(skip_whitespace)
+        .any(|line| KEYWORDS.iter().any(|keyword| line.starts_with(keyword)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn guess(bytes: &[u8]) -> Option<(Format, Encoding)> {
+        detect(bytes).map(|guess| (guess.format, guess.encoding))
+    }
+
+    #[test]
+    fn test_detect_magic() {
+        assert_eq!(guess(b"Kaydara FBX Binary  \x00\x1a\x00"), Some((Format::Fbx, Encoding::Binary)));
+        assert_eq!(guess(b"glTF\x02\x00\x00\x00\x10\x00\x00\x00"), Some((Format::Gltf, Encoding::Binary)));
+        assert_eq!(guess(b"BLENDER-v279"), Some((Format::Blender, Encoding::Binary)));
+        assert_eq!(guess(b"ply\r\nformat binary_little_endian 1.0\r\n"), Some((Format::Ply, Encoding::Binary)));
+        assert_eq!(guess(b"ply\nformat ascii 1.0\n"), Some((Format::Ply, Encoding::Ascii)));
+        assert_eq!(guess(b"MM\x0e\x00\x00\x00\x02\x00\x0a\x00\x00\x00\x03\x00"), Some((Format::ThreeDs, Encoding::Binary)));
+
+        let mut stl = vec![0; 84 + 50];
+
+        stl[80] = 1;
+
+        assert_eq!(guess(&stl), Some((Format::Stl, Encoding::Binary)));
+
+        stl.push(0);
+
+        assert_eq!(guess(&stl), None);
+        assert_eq!(detect_reader(&stl[..]).unwrap().map(|guess| guess.confidence), Some(Confidence::Heuristic));
+
+        let mut large_stl = vec![0; 84 + 50 * 100];
+
+        large_stl[80] = 100;
+
+        assert_eq!(guess(&large_stl), Some((Format::Stl, Encoding::Binary)));
+    }
+
+    #[test]
+    fn test_detect_window() {
+        let mut text = vec![b'#'; HEADER_LENGTH];
+
+        text.extend_from_slice(b"\nv 1.0 1.0 -1.0\n");
+
+        assert_eq!(guess(&text), None);
+        assert_eq!(detect_reader(&text[..]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_detect_text() {
+        assert_eq!(guess(b"; FBX 7.4.0 project file\n"), Some((Format::Fbx, Encoding::Ascii)));
+        assert_eq!(guess(b"\xEF\xBB\xBF<?xml version=\"1.0\"?>\n<COLLADA version=\"1.4.1\">"), Some((Format::Collada, Encoding::Ascii)));
+        assert_eq!(guess(b"  {\n  \"asset\": { \"version\": \"2.0\" }\n}"), Some((Format::Gltf, Encoding::Ascii)));
+        assert_eq!(guess(b"solid cube\n  facet normal 0 0 1\n"), Some((Format::Stl, Encoding::Ascii)));
+        assert_eq!(guess(b"# Blender OBJ\nmtllib cube.mtl\nv 1.0 1.0 -1.0\n"), Some((Format::Obj, Encoding::Ascii)));
+        assert_eq!(guess(b"just some text\n"), None);
+
+        assert_eq!(detect(b"glTF\x02\x00\x00\x00").unwrap().extension(), "glb");
+    }
+}
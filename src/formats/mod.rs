@@ -0,0 +1,122 @@
+//! Contains all formats and their file extensions supported by Assimp
+//!
+//! See http://www.assimp.org/main_features_formats.html
+
+use libc::{c_char, c_uint};
+
+use std::ffi::{CString, CStr};
+use std::mem;
+
+use ffi;
+
+pub mod detect;
+
+pub use self::detect::{Format, Encoding, Confidence, FormatGuess, detect, detect_reader};
+
+bitflags! {
+    /// Importer capability bitflags
+    pub flags ImporterFlags: c_uint {
+        const SUPPORT_TEXT = ffi::IMPORTER_FLAG_SUPPORT_TEXT as c_uint,
+        const SUPPORT_BINARY = ffi::IMPORTER_FLAG_SUPPORT_BINARY as c_uint,
+        const SUPPORT_COMPRESSED = ffi::IMPORTER_FLAG_SUPPORT_COMPRESSED as c_uint,
+        const SUPPORT_LIMITED = ffi::IMPORTER_FLAG_SUPPORT_LIMITED as c_uint,
+        const EXPERIMENTAL = ffi::IMPORTER_FLAG_EXPERIMENTAL as c_uint
+    }
+}
+
+/// Describes one of the importers built into Assimp
+#[derive(Clone, Debug)]
+pub struct ImporterDesc {
+    pub name: String,
+    pub author: String,
+    pub maintainer: String,
+    pub comments: String,
+    pub flags: ImporterFlags,
+    /// Minimum `(major, minor)` version of the format supported, or `(0, 0)` if there are no versions
+    pub min_version: (u32, u32),
+    /// Maximum `(major, minor)` version of the format supported, or `(0, 0)` if there are no versions
+    pub max_version: (u32, u32),
+    /// File extensions handled by the importer, without leading dots
+    pub extensions: Vec<String>,
+}
+
+impl ImporterDesc {
+    fn from_raw(raw: &ffi::AiImporterDesc) -> ImporterDesc {
+        fn string(s: *const c_char) -> String {
+            if s.is_null() { String::new() } else {
+                unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned()
+            }
+        }
+
+        ImporterDesc {
+            name: string(raw.name),
+            author: string(raw.author),
+            maintainer: string(raw.maintainer),
+            comments: string(raw.comments),
+            flags: ImporterFlags::from_bits_truncate(raw.flags),
+            min_version: (raw.min_major as u32, raw.min_minor as u32),
+            max_version: (raw.max_major as u32, raw.max_minor as u32),
+            extensions: string(raw.file_extensions).split_whitespace().map(|ext| ext.trim_left_matches('.').to_string()).collect(),
+        }
+    }
+
+    /// Checks if the importer handles the given extension, ignoring case and any leading dot
+    pub fn supports_extension(&self, ext: &str) -> bool {
+        let ext = ext.trim_left_matches('.');
+
+        self.extensions.iter().any(|e| e.eq_ignore_ascii_case(ext))
+    }
+}
+
+/// Returns descriptions of all importers built into Assimp,
+/// via `aiGetImportFormatCount` and `aiGetImportFormatDescription`
+pub fn importers() -> Vec<ImporterDesc> {
+    let count = unsafe { ffi::aiGetImportFormatCount() };
+
+    (0..count).filter_map(|index| {
+        unsafe { ffi::aiGetImportFormatDescription(index).as_ref() }.map(ImporterDesc::from_raw)
+    }).collect()
+}
+
+/// Returns the file extensions of all formats Assimp can import, without leading dots
+pub fn extensions() -> Vec<String> {
+    let mut list: ffi::AiString = unsafe { mem::zeroed() };
+
+    unsafe { ffi::aiGetExtensionList(&mut list) };
+
+    // The list is formatted like "*.3ds;*.obj;*.dae"
+    list.to_string_lossy()
+        .split(';')
+        .map(|ext| ext.trim().trim_left_matches('*').trim_left_matches('.').to_string())
+        .filter(|ext| !ext.is_empty())
+        .collect()
+}
+
+/// Calls `aiIsExtensionSupported` to check if Assimp can handle the extension
+pub fn is_extension_supported(ext: &str) -> bool {
+    let c_str = CString::new(ext).unwrap();
+
+    unsafe { ::ffi::aiIsExtensionSupported(c_str.as_ptr()) == ::ffi::TRUE }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_supported() {
+        assert!(is_extension_supported("obj"));
+        assert!(is_extension_supported("ply"));
+        assert!(is_extension_supported("dae"));
+    }
+
+    #[test]
+    fn test_importers() {
+        let importers = importers();
+
+        assert!(importers.iter().any(|desc| desc.supports_extension("obj")));
+        assert!(importers.iter().any(|desc| desc.supports_extension(".DAE")));
+
+        assert!(extensions().iter().any(|ext| ext == "ply"));
+    }
+}
@@ -0,0 +1,213 @@
+//! Reusable import sessions
+
+use std::ffi::CString;
+use std::mem;
+use std::panic;
+use std::path::Path;
+use std::ptr;
+
+use ffi;
+use error::*;
+use io::{AssimpIO, CustomIO, FilteredIO};
+use logging::{LogCapture, ImportLog};
+use postprocess::{PostprocessEffect, PostprocessEffectBuilder};
+use properties::ImportProperties;
+use options::ImporterOptions;
+use scene::Scene;
+
+/// An import session, which holds import properties, default postprocess effects
+/// and optionally a custom IO system to be used for every file it reads.
+///
+/// Errors are taken from the messages Assimp emits on the importing thread, rather than its global
+/// error string, so importers used in parallel on different threads never see each other's errors.
+pub struct Importer<'a> {
+    properties: ImportProperties,
+    effects: Option<PostprocessEffect>,
+    io: Option<Box<AssimpIO + 'a>>,
+    ignored_extensions: Vec<String>,
+    log: ImportLog,
+}
+
+impl<'a> Default for Importer<'a> {
+    fn default() -> Importer<'a> {
+        Importer::new()
+    }
+}
+
+impl<'a> Importer<'a> {
+    pub fn new() -> Importer<'a> {
+        Importer {
+            properties: ImportProperties::new(),
+            effects: None,
+            io: None,
+            ignored_extensions: Vec::new(),
+            log: ImportLog::default(),
+        }
+    }
+
+    #[inline]
+    pub fn properties(&self) -> &ImportProperties {
+        &self.properties
+    }
+
+    /// Get the import properties for modification
+    #[inline]
+    pub fn properties_mut(&mut self) -> &mut ImportProperties {
+        &mut self.properties
+    }
+
+    /// Set the import properties for the given importer options,
+    /// and skip the auxiliary files they ignore
+    pub fn set_options<O: ImporterOptions>(&mut self, options: &O) -> &mut Importer<'a> {
+        options.apply(&mut self.properties);
+
+        for ext in options.ignored_extensions() {
+            if !self.ignored_extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)) {
+                self.ignored_extensions.push(ext.to_string());
+            }
+        }
+
+        self
+    }
+
+    /// Get the extensions of files the importer refuses to open
+    #[inline]
+    pub fn ignored_extensions(&self) -> &[String] {
+        &self.ignored_extensions
+    }
+
+    #[inline]
+    pub fn effects(&self) -> Option<PostprocessEffect> {
+        self.effects
+    }
+
+    /// Set the postprocess effects applied by `read`
+    #[inline]
+    pub fn set_effects(&mut self, effects: Option<PostprocessEffect>) -> &mut Importer<'a> {
+        self.effects = effects;
+
+        self
+    }
+
+    /// Use the effects and step configuration of the builder for every following import
+    pub fn set_postprocess(&mut self, builder: PostprocessEffectBuilder) -> &mut Importer<'a> {
+        let (effects, properties) = builder.build();
+
+        self.properties.extend(&properties);
+        self.effects = Some(effects);
+
+        self
+    }
+
+    /// Use a custom IO system for every following import. Borrowed IO systems, like `&mut CustomIO`, work too.
+    #[inline]
+    pub fn set_io<IO: AssimpIO + 'a>(&mut self, io: IO) -> &mut Importer<'a> {
+        self.io = Some(Box::new(io));
+
+        self
+    }
+
+    /// Stop using a custom IO system, returning it if there was one
+    #[inline]
+    pub fn clear_io(&mut self) -> Option<Box<AssimpIO + 'a>> {
+        self.io.take()
+    }
+
+    /// Get the messages Assimp emitted during the last import
+    #[inline]
+    pub fn log(&self) -> &ImportLog {
+        &self.log
+    }
+
+    /// Take the messages Assimp emitted during the last import, leaving an empty log behind
+    #[inline]
+    pub fn take_log(&mut self) -> ImportLog {
+        mem::replace(&mut self.log, ImportLog::default())
+    }
+
+    /// Import a scene, applying the default postprocess effects
+    #[inline]
+    pub fn read<'s, P: AsRef<Path>>(&mut self, path: P) -> AiResult<Scene<'s>> {
+        let effects = self.effects;
+
+        self.read_with(path, effects)
+    }
+
+    /// Import a scene, applying the given postprocess effects instead of the default ones.
+    ///
+    /// If the import fails after a stream error occurred in the custom IO system,
+    /// that error is returned as `AiError::Io`. If any callback of the IO system panicked,
+    /// the panic is resumed once Assimp has returned.
+    pub fn read_with<'s, P: AsRef<Path>>(&mut self, path: P, effects: Option<PostprocessEffect>) -> AiResult<Scene<'s>> {
+        let path = path.as_ref();
+
+        let c_path = match path.to_str() {
+            Some(path) => try_throw!(CString::new(path)),
+            None => throw!(AiError::Internal(format!("Path is not valid UTF-8: {}", path.display()))),
+        };
+
+        let store = try_rethrow!(self.properties.to_store());
+
+        let mut default_io;
+        let mut filtered;
+
+        let mut io: Option<&mut AssimpIO> = match self.io {
+            Some(ref mut io) => Some(&mut **io),
+            None => None,
+        };
+
+        if !self.ignored_extensions.is_empty() {
+            let inner: &mut AssimpIO = match io.take() {
+                Some(io) => io,
+                None => {
+                    // Without a custom IO system, Assimp's default one has to be replaced to filter files
+                    default_io = CustomIO::default();
+                    &mut default_io
+                }
+            };
+
+            filtered = FilteredIO::new(inner, &self.ignored_extensions);
+            io = Some(&mut filtered);
+        }
+
+        if let Some(ref mut io) = io {
+            // Discard any failures left over from previous imports
+            io.take_error();
+            io.take_panic();
+        }
+
+        let capture = LogCapture::start();
+
+        let scene_ptr = unsafe {
+            ffi::aiImportFileExWithProperties(c_path.as_ptr(), match effects {
+                None => 0,
+                Some(flags) => flags.bits(),
+            }, match io {
+                Some(ref mut io) => io.get(),
+                None => ptr::null_mut(),
+            }, store.as_ptr())
+        };
+
+        self.log = capture.finish();
+
+        let scene = Scene::from_import(scene_ptr, path, self.log.validation_warnings());
+
+        if let Some(ref mut io) = io {
+            if let Some(payload) = io.take_panic() {
+                panic::resume_unwind(payload);
+            }
+
+            if !scene.valid() {
+                if let Some(err) = io.take_error() {
+                    throw!(AiError::Io(err));
+                }
+            }
+        }
+
+        if !scene.valid() {
+            throw!(AiError::import_failed(ImportContext::new(path, effects), &self.log));
+        }
+
+        Ok(scene)
+    }
+}
@@ -0,0 +1,293 @@
+//! Buffering wrapper for any `IOHandler`
+
+use std::cmp;
+use std::path::Path;
+use std::io::prelude::*;
+use std::io::{self, SeekFrom};
+
+use super::{IOStream, IOHandler, OpenMode};
+
+/// Default capacity of the read buffer, in bytes
+pub const DEFAULT_BUFFER_CAPACITY: usize = 64 * 1024;
+
+/// Stream created by `BufferedIOHandler`
+///
+/// Reads are served from an internal buffer, and the position and size of the stream
+/// are tracked so that querying them does not touch the underlying stream.
+///
+/// Writes are passed directly to the underlying stream. Streams opened for appending are positioned
+/// by the underlying stream on every write, so the tracked position may not match it afterwards.
+pub struct BufferedStream<S: IOStream> {
+    inner: S,
+    buf: Box<[u8]>,
+    /// Position of the start of the buffer within the stream
+    buf_start: u64,
+    /// Number of valid bytes in the buffer
+    buf_len: usize,
+    /// Position of the stream as seen by the user
+    pos: u64,
+    /// Position of the underlying stream, if known
+    inner_pos: Option<u64>,
+    /// Size of the stream, if known
+    size: Option<u64>,
+}
+
+impl<S: IOStream> BufferedStream<S> {
+    /// Wrap the given stream with the default buffer capacity
+    #[inline]
+    pub fn new(inner: S) -> BufferedStream<S> {
+        BufferedStream::with_capacity(DEFAULT_BUFFER_CAPACITY, inner)
+    }
+
+    /// Wrap the given stream with a read buffer of `capacity` bytes
+    pub fn with_capacity(capacity: usize, inner: S) -> BufferedStream<S> {
+        BufferedStream {
+            inner: inner,
+            buf: vec![0; capacity].into_boxed_slice(),
+            buf_start: 0,
+            buf_len: 0,
+            pos: 0,
+            inner_pos: None,
+            size: None,
+        }
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    #[inline]
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Gets the size of the stream, only querying the underlying stream the first time
+    pub fn size(&mut self) -> io::Result<u64> {
+        if let Some(size) = self.size {
+            return Ok(size);
+        }
+
+        let size = try!(self.inner.seek(SeekFrom::End(0)));
+
+        self.inner_pos = Some(size);
+        self.size = Some(size);
+
+        Ok(size)
+    }
+
+    /// Moves the underlying stream to the current position, if it isn't already there
+    fn sync(&mut self) -> io::Result<()> {
+        if self.inner_pos != Some(self.pos) {
+            // Forget the position first, in case seeking fails
+            self.inner_pos = None;
+            self.inner_pos = Some(try!(self.inner.seek(SeekFrom::Start(self.pos))));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the buffered bytes at the current position
+    #[inline]
+    fn buffered(&self) -> &[u8] {
+        if self.pos >= self.buf_start && self.pos < self.buf_start + self.buf_len as u64 {
+            &self.buf[(self.pos - self.buf_start) as usize..self.buf_len]
+        } else {
+            &[]
+        }
+    }
+}
+
+impl<S: IOStream> Read for BufferedStream<S> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.buffered().is_empty() {
+            try!(self.sync());
+
+            // Large reads bypass the buffer entirely
+            if out.len() >= self.buf.len() {
+                let amt = try!(self.inner.read(out));
+
+                self.pos += amt as u64;
+                self.inner_pos = Some(self.pos);
+
+                return Ok(amt);
+            }
+
+            self.buf_len = 0;
+
+            let amt = try!(self.inner.read(&mut self.buf));
+
+            self.buf_start = self.pos;
+            self.buf_len = amt;
+            self.inner_pos = Some(self.pos + amt as u64);
+        }
+
+        let amt = {
+            let buffered = self.buffered();
+            let amt = cmp::min(buffered.len(), out.len());
+
+            out[..amt].copy_from_slice(&buffered[..amt]);
+
+            amt
+        };
+
+        self.pos += amt as u64;
+
+        Ok(amt)
+    }
+}
+
+impl<S: IOStream> Write for BufferedStream<S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Anything buffered may be overwritten
+        self.buf_len = 0;
+
+        try!(self.sync());
+
+        let res = self.inner.write(buf);
+
+        match res {
+            Ok(amt) => {
+                self.pos += amt as u64;
+                self.inner_pos = Some(self.pos);
+                self.size = self.size.map(|size| cmp::max(size, self.pos));
+            }
+            Err(_) => {
+                self.inner_pos = None;
+            }
+        }
+
+        res
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<S: IOStream> Seek for BufferedStream<S> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(pos) => {
+                self.pos = pos;
+
+                return Ok(pos);
+            }
+            SeekFrom::Current(offset) => (self.pos, offset),
+            SeekFrom::End(offset) => (try!(self.size()), offset),
+        };
+
+        let pos = if offset >= 0 {
+            base.checked_add(offset as u64)
+        } else {
+            base.checked_sub(offset.wrapping_neg() as u64)
+        };
+
+        match pos {
+            Some(pos) => {
+                self.pos = pos;
+
+                Ok(pos)
+            }
+            None => Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid seek to a negative or overflowing position")),
+        }
+    }
+}
+
+/// `IOHandler` which wraps another handler and buffers every stream it opens.
+///
+/// Useful for slow or network filesystems, where many small reads and seeks are costly.
+pub struct BufferedIOHandler<H> {
+    inner: H,
+    capacity: usize,
+}
+
+impl<H> BufferedIOHandler<H> {
+    /// Wrap the given handler with the default buffer capacity
+    #[inline]
+    pub fn new(inner: H) -> BufferedIOHandler<H> {
+        BufferedIOHandler::with_capacity(DEFAULT_BUFFER_CAPACITY, inner)
+    }
+
+    /// Wrap the given handler, giving every stream a read buffer of `capacity` bytes
+    #[inline]
+    pub fn with_capacity(capacity: usize, inner: H) -> BufferedIOHandler<H> {
+        BufferedIOHandler {
+            inner: inner,
+            capacity: capacity,
+        }
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    #[inline]
+    pub fn get_ref(&self) -> &H {
+        &self.inner
+    }
+}
+
+impl<S, H> IOHandler<BufferedStream<S>> for BufferedIOHandler<H> where S: IOStream, H: IOHandler<S> {
+    fn open<P: AsRef<Path>>(&self, path: P, mode: OpenMode) -> io::Result<BufferedStream<S>> {
+        self.inner.open(path, mode).map(|stream| BufferedStream::with_capacity(self.capacity, stream))
+    }
+
+    fn close(&self, stream: BufferedStream<S>) -> io::Result<()> {
+        self.inner.close(stream.into_inner())
+    }
+
+    #[inline]
+    fn size(&self, stream: &mut BufferedStream<S>) -> io::Result<u64> {
+        stream.size()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::prelude::*;
+    use std::io::{Cursor, SeekFrom};
+
+    use super::*;
+
+    #[test]
+    fn test_buffered_stream() {
+        let data: Vec<u8> = (0..100).collect();
+
+        let mut stream = BufferedStream::with_capacity(16, Cursor::new(data.clone()));
+
+        let mut out = [0; 10];
+
+        assert_eq!(stream.read(&mut out).unwrap(), 10);
+        assert_eq!(&out, &data[0..10]);
+
+        // Only the rest of the buffer is returned
+        assert_eq!(stream.read(&mut out).unwrap(), 6);
+        assert_eq!(&out[..6], &data[10..16]);
+
+        assert_eq!(stream.seek(SeekFrom::Current(0)).unwrap(), 16);
+        assert_eq!(stream.seek(SeekFrom::End(0)).unwrap(), 100);
+        assert_eq!(stream.seek(SeekFrom::End(-5)).unwrap(), 95);
+
+        let mut rest = Vec::new();
+
+        assert_eq!(stream.read_to_end(&mut rest).unwrap(), 5);
+        assert_eq!(&rest[..], &data[95..]);
+
+        assert!(stream.seek(SeekFrom::Current(-200)).is_err());
+
+        stream.seek(SeekFrom::Start(2)).unwrap();
+
+        assert_eq!(stream.write(&[255, 255]).unwrap(), 2);
+        assert_eq!(stream.seek(SeekFrom::Start(0)).unwrap(), 0);
+        assert_eq!(stream.read(&mut out).unwrap(), 10);
+        assert_eq!(&out[..4], &[0, 1, 255, 255]);
+    }
+}
@@ -0,0 +1,109 @@
+//! Filtering wrapper for any `AssimpIO`, which refuses to open files with certain extensions
+
+use libc::c_char;
+
+use std::any::Any;
+use std::ffi::CStr;
+use std::io;
+use std::path::Path;
+use std::ptr;
+
+use ::ffi::{AiFile, AiFileIO, AiUserData};
+
+use super::AssimpIO;
+
+/// State owned by a `FilteredIO` instance and pointed to by its `AiFileIO` user data
+struct FilterState<IO> {
+    inner: IO,
+    extensions: Vec<String>,
+}
+
+impl<IO> FilterState<IO> {
+    fn is_excluded(&self, path: &Path) -> bool {
+        path.extension().and_then(|ext| ext.to_str()).map_or(false, |ext| {
+            self.extensions.iter().any(|e| e.eq_ignore_ascii_case(ext))
+        })
+    }
+}
+
+/// IO system which wraps another one and refuses to open files with any of the given extensions,
+/// as if they didn't exist.
+///
+/// Assimp treats missing auxiliary files, like the `.mtl` files of OBJ models, as optional,
+/// so this can be used to skip them.
+pub struct FilteredIO<IO: AssimpIO> {
+    io: AiFileIO,
+    state: Box<FilterState<IO>>,
+}
+
+impl<IO: AssimpIO> FilteredIO<IO> {
+    /// Wrap the given IO system, excluding files with the given extensions, without leading dots and ignoring case
+    pub fn new<I, S>(inner: IO, extensions: I) -> FilteredIO<IO> where I: IntoIterator<Item = S>, S: AsRef<str> {
+        let mut state = Box::new(FilterState {
+            inner: inner,
+            extensions: extensions.into_iter().map(|ext| ext.as_ref().trim_left_matches('.').to_string()).collect(),
+        });
+
+        FilteredIO {
+            io: AiFileIO {
+                open: open_proc::<IO>,
+                close: close_proc::<IO>,
+                user_data: &mut *state as *mut FilterState<IO> as AiUserData,
+            },
+            state: state,
+        }
+    }
+
+    /// Gets the wrapped IO system
+    #[inline]
+    pub fn get_ref(&self) -> &IO {
+        &self.state.inner
+    }
+
+    /// Gets the excluded extensions
+    #[inline]
+    pub fn extensions(&self) -> &[String] {
+        &self.state.extensions
+    }
+}
+
+impl<IO: AssimpIO> AssimpIO for FilteredIO<IO> {
+    #[inline]
+    fn get(&mut self) -> &mut AiFileIO {
+        &mut self.io
+    }
+
+    #[inline]
+    fn take_error(&mut self) -> Option<io::Error> {
+        self.state.inner.take_error()
+    }
+
+    #[inline]
+    fn take_panic(&mut self) -> Option<Box<Any + Send>> {
+        self.state.inner.take_panic()
+    }
+}
+
+extern "C" fn open_proc<IO: AssimpIO>(file_io: *mut AiFileIO, path: *const c_char, mode: *const c_char) -> *mut AiFile {
+    let state: &mut FilterState<IO> = user_data!(file_io);
+
+    c_assert!(!path.is_null());
+
+    if let Ok(p) = unsafe { CStr::from_ptr(path) }.to_str() {
+        if state.is_excluded(Path::new(p)) {
+            return ptr::null_mut();
+        }
+    }
+
+    let inner = state.inner.get() as *mut AiFileIO;
+
+    unsafe { ((*inner).open)(inner, path, mode) }
+}
+
+extern "C" fn close_proc<IO: AssimpIO>(file_io: *mut AiFileIO, file: *mut AiFile) {
+    let state: &mut FilterState<IO> = user_data!(file_io);
+
+    let inner = state.inner.get() as *mut AiFileIO;
+
+    unsafe { ((*inner).close)(inner, file) }
+}
@@ -0,0 +1,226 @@
+//! Profiling wrapper for any `IOHandler`, which records statistics for every opened file
+
+use std::path::{Path, PathBuf};
+use std::io::prelude::*;
+use std::io::{self, SeekFrom};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::{IOStream, IOHandler, OpenMode};
+
+/// Statistics recorded for a single opened file
+#[derive(Clone, Debug)]
+pub struct FileStats {
+    /// Path the file was opened with
+    pub path: PathBuf,
+    /// Mode the file was opened with
+    pub mode: OpenMode,
+    /// Total number of bytes read
+    pub bytes_read: u64,
+    /// Number of read calls
+    pub reads: u64,
+    /// Total number of bytes written
+    pub bytes_written: u64,
+    /// Number of write calls
+    pub writes: u64,
+    /// Number of seeks, not including those made to determine the position of the stream
+    pub seeks: u64,
+    /// Number of times the current position was queried
+    pub tells: u64,
+    /// Number of times the size of the stream was queried
+    pub size_queries: u64,
+    /// Total time spent opening, closing and accessing the file
+    pub time: Duration,
+}
+
+impl FileStats {
+    fn new(path: &Path, mode: OpenMode) -> FileStats {
+        FileStats {
+            path: path.to_path_buf(),
+            mode: mode,
+            bytes_read: 0,
+            reads: 0,
+            bytes_written: 0,
+            writes: 0,
+            seeks: 0,
+            tells: 0,
+            size_queries: 0,
+            time: Duration::from_secs(0),
+        }
+    }
+}
+
+/// Stream created by `InstrumentedIOHandler`, which records statistics for every call
+pub struct InstrumentedStream<S: IOStream> {
+    inner: S,
+    stats: FileStats,
+}
+
+impl<S: IOStream> InstrumentedStream<S> {
+    /// Get the statistics recorded so far
+    #[inline]
+    pub fn stats(&self) -> &FileStats {
+        &self.stats
+    }
+
+    #[inline]
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: IOStream> Read for InstrumentedStream<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let start = Instant::now();
+
+        let res = self.inner.read(buf);
+
+        self.stats.time += start.elapsed();
+        self.stats.reads += 1;
+
+        if let Ok(amt) = res {
+            self.stats.bytes_read += amt as u64;
+        }
+
+        res
+    }
+}
+
+impl<S: IOStream> Write for InstrumentedStream<S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let start = Instant::now();
+
+        let res = self.inner.write(buf);
+
+        self.stats.time += start.elapsed();
+        self.stats.writes += 1;
+
+        if let Ok(amt) = res {
+            self.stats.bytes_written += amt as u64;
+        }
+
+        res
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let start = Instant::now();
+
+        let res = self.inner.flush();
+
+        self.stats.time += start.elapsed();
+
+        res
+    }
+}
+
+impl<S: IOStream> Seek for InstrumentedStream<S> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let start = Instant::now();
+
+        let res = self.inner.seek(pos);
+
+        self.stats.time += start.elapsed();
+
+        match pos {
+            SeekFrom::Current(0) => self.stats.tells += 1,
+            _ => self.stats.seeks += 1,
+        }
+
+        res
+    }
+}
+
+/// `IOHandler` which wraps another handler and records statistics for every file it opens.
+///
+/// Statistics for a file are available once it has been closed, which Assimp does before returning from an import.
+/// They can be retrieved through `CustomIO::handler`.
+pub struct InstrumentedIOHandler<H> {
+    inner: H,
+    stats: Mutex<Vec<FileStats>>,
+}
+
+impl<H> InstrumentedIOHandler<H> {
+    /// Wrap the given handler
+    pub fn new(inner: H) -> InstrumentedIOHandler<H> {
+        InstrumentedIOHandler {
+            inner: inner,
+            stats: Mutex::new(Vec::new()),
+        }
+    }
+
+    #[inline]
+    pub fn get_ref(&self) -> &H {
+        &self.inner
+    }
+
+    /// Get a copy of the statistics of every closed file, in the order they were closed
+    pub fn stats(&self) -> Vec<FileStats> {
+        match self.stats.lock() {
+            Ok(stats) => stats.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Take the statistics of every closed file, in the order they were closed, leaving none behind
+    pub fn take_stats(&self) -> Vec<FileStats> {
+        let mut stats = match self.stats.lock() {
+            Ok(stats) => stats,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        stats.drain(..).collect()
+    }
+}
+
+impl<S, H> IOHandler<InstrumentedStream<S>> for InstrumentedIOHandler<H> where S: IOStream, H: IOHandler<S> {
+    fn open<P: AsRef<Path>>(&self, path: P, mode: OpenMode) -> io::Result<InstrumentedStream<S>> {
+        let path = path.as_ref();
+
+        let start = Instant::now();
+
+        let inner = try!(self.inner.open(path, mode));
+
+        let mut stats = FileStats::new(path, mode);
+
+        stats.time = start.elapsed();
+
+        Ok(InstrumentedStream {
+            inner: inner,
+            stats: stats,
+        })
+    }
+
+    fn close(&self, stream: InstrumentedStream<S>) -> io::Result<()> {
+        let InstrumentedStream { inner, mut stats } = stream;
+
+        let start = Instant::now();
+
+        let res = self.inner.close(inner);
+
+        stats.time += start.elapsed();
+
+        match self.stats.lock() {
+            Ok(mut all) => all.push(stats),
+            Err(poisoned) => poisoned.into_inner().push(stats),
+        }
+
+        res
+    }
+
+    /// Counts the size query, without counting the seeks the inner handler may make to answer it
+    fn size(&self, stream: &mut InstrumentedStream<S>) -> io::Result<u64> {
+        let start = Instant::now();
+
+        let res = self.inner.size(&mut stream.inner);
+
+        stream.stats.time += start.elapsed();
+        stream.stats.size_queries += 1;
+
+        res
+    }
+}
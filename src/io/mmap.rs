@@ -0,0 +1,36 @@
+//! Memory-mapped file streams
+
+use std::path::Path;
+use std::io::{self, Cursor};
+use std::fs::File;
+
+use memmap::Mmap;
+
+use super::{IOHandler, OpenMode, ReadOnlyStream};
+
+/// Read-only stream over a memory-mapped file
+pub type MmapStream = ReadOnlyStream<Cursor<Mmap>>;
+
+/// `IOHandler` which serves files as read-only memory maps
+///
+/// Requests to open files for writing are refused.
+pub struct MmapIOHandler;
+
+impl IOHandler<MmapStream> for MmapIOHandler {
+    fn open<P: AsRef<Path>>(&self, path: P, mode: OpenMode) -> io::Result<MmapStream> {
+        if mode.is_write() {
+            return Err(io::Error::new(io::ErrorKind::PermissionDenied, "Memory-mapped files are read-only"));
+        }
+
+        let file = try!(File::open(path));
+
+        // The map is only valid as long as the file isn't modified elsewhere, same as any other reader
+        let map = try!(unsafe { Mmap::map(&file) });
+
+        Ok(ReadOnlyStream::new(Cursor::new(map)))
+    }
+
+    fn close(&self, _: MmapStream) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,709 @@
+use std::path::Path;
+use std::io::prelude::*;
+use std::io::{self, SeekFrom};
+use std::fs::{File, OpenOptions};
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::any::Any;
+
+use ::ffi::{AiFileIO, AiUserData};
+
+macro_rules! user_data {
+    ($file:expr) => {unsafe {
+        c_assert!(!$file.is_null());
+
+        let user_data = (*$file).user_data as *mut _;
+
+        c_assert!(!user_data.is_null());
+
+        &mut *user_data
+    }}
+}
+
+pub mod instrumented;
+pub mod buffered;
+pub mod progress;
+pub mod filtered;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+
+pub use self::instrumented::{InstrumentedIOHandler, InstrumentedStream, FileStats};
+pub use self::buffered::{BufferedIOHandler, BufferedStream};
+pub use self::progress::{ProgressIOHandler, ProgressStream, Progress, ProgressAction};
+pub use self::filtered::FilteredIO;
+#[cfg(feature = "mmap")]
+pub use self::mmap::{MmapIOHandler, MmapStream};
+
+/// Represents a type which can provide access to a set of callbacks which can
+/// be used within Assimp's custom IO API.
+///
+/// It is inadvisable to use this directly, as it requires knowledge of the C API.
+/// Try using `CustomIO` instead.
+pub trait AssimpIO {
+    fn get(&mut self) -> &mut ::ffi::AiFileIO;
+
+    /// Takes the first stream error encountered since the last call, if any.
+    ///
+    /// Errors cannot be propagated through Assimp's C callbacks, so they are stored
+    /// until the import has finished.
+    fn take_error(&mut self) -> Option<io::Error>;
+
+    /// Takes the payload of the first panic caught within a callback since the last call, if any.
+    ///
+    /// Unwinding into Assimp is undefined behavior, so panics are caught at the FFI boundary
+    /// and should be resumed once Assimp has returned.
+    fn take_panic(&mut self) -> Option<Box<Any + Send>>;
+}
+
+impl<'a, T: AssimpIO + ?Sized> AssimpIO for &'a mut T {
+    #[inline]
+    fn get(&mut self) -> &mut ::ffi::AiFileIO {
+        (**self).get()
+    }
+
+    #[inline]
+    fn take_error(&mut self) -> Option<io::Error> {
+        (**self).take_error()
+    }
+
+    #[inline]
+    fn take_panic(&mut self) -> Option<Box<Any + Send>> {
+        (**self).take_panic()
+    }
+}
+
+/// Some type that is `Seek + Read + Write`
+///
+/// Automatically derived by any type that fulfils those conditions.
+///
+/// Streams are not required to be `'static`, so they may borrow data for the length of the import call.
+pub trait IOStream: Seek + Read + Write {}
+
+impl<T> IOStream for T where T: Seek + Read + Write {}
+
+/// Read-only stream adapter, which turns any `Seek + Read` type into an `IOStream`
+///
+/// Writes always report zero bytes written, which Assimp treats as a failure.
+pub struct ReadOnlyStream<R: Seek + Read> {
+    inner: R,
+}
+
+impl<R: Seek + Read> ReadOnlyStream<R> {
+    #[inline]
+    pub fn new(inner: R) -> ReadOnlyStream<R> {
+        ReadOnlyStream { inner: inner }
+    }
+
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Seek + Read> Read for ReadOnlyStream<R> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<R: Seek + Read> Seek for ReadOnlyStream<R> {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl<R: Seek + Read> Write for ReadOnlyStream<R> {
+    #[inline]
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        Ok(0)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The mode Assimp requested a file to be opened with, mirroring the modes of C's `fopen`
+///
+/// The `b` and `t` modifiers are ignored, since Rust streams are always binary.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum OpenMode {
+    /// `"r"`: Open an existing file for reading
+    Read,
+    /// `"w"`: Create or truncate a file for writing
+    Write,
+    /// `"a"`: Create or open a file for writing at the end of it
+    Append,
+    /// `"r+"`: Open an existing file for reading and writing
+    ReadUpdate,
+    /// `"w+"`: Create or truncate a file for reading and writing
+    WriteUpdate,
+    /// `"a+"`: Create or open a file for reading, and writing at the end of it
+    AppendUpdate,
+}
+
+impl OpenMode {
+    /// Parses a C `fopen` mode string such as `"rb"` or `"w+"`
+    pub fn from_c_mode(mode: &str) -> Option<OpenMode> {
+        let update = mode.contains('+');
+
+        match (mode.chars().next(), update) {
+            (Some('r'), false) => Some(OpenMode::Read),
+            (Some('w'), false) => Some(OpenMode::Write),
+            (Some('a'), false) => Some(OpenMode::Append),
+            (Some('r'), true) => Some(OpenMode::ReadUpdate),
+            (Some('w'), true) => Some(OpenMode::WriteUpdate),
+            (Some('a'), true) => Some(OpenMode::AppendUpdate),
+            _ => None,
+        }
+    }
+
+    /// Checks if the stream should be readable
+    #[inline]
+    pub fn is_read(&self) -> bool {
+        match *self {
+            OpenMode::Write | OpenMode::Append => false,
+            _ => true,
+        }
+    }
+
+    /// Checks if the stream should be writable
+    #[inline]
+    pub fn is_write(&self) -> bool {
+        *self != OpenMode::Read
+    }
+
+    /// Creates a set of `OpenOptions` matching the mode
+    pub fn options(&self) -> OpenOptions {
+        let mut options = OpenOptions::new();
+
+        match *self {
+            OpenMode::Read => options.read(true),
+            OpenMode::Write => options.write(true).create(true).truncate(true),
+            OpenMode::Append => options.append(true).create(true),
+            OpenMode::ReadUpdate => options.read(true).write(true),
+            OpenMode::WriteUpdate => options.read(true).write(true).create(true).truncate(true),
+            OpenMode::AppendUpdate => options.read(true).append(true).create(true),
+        };
+
+        options
+    }
+}
+
+/// Provides methods for opening and closing streams
+pub trait IOHandler<S: IOStream>: Send + Sync {
+    fn open<P: AsRef<Path>>(&self, path: P, mode: OpenMode) -> io::Result<S>;
+    fn close(&self, stream: S) -> io::Result<()>;
+
+    /// Gets the total size of a stream opened by this handler, which is called for every size query Assimp makes.
+    ///
+    /// The default implementation seeks to the end of the stream and back to the original position.
+    /// Handlers wrapping another handler should forward this to it.
+    fn size(&self, stream: &mut S) -> io::Result<u64> {
+        let cur = try!(stream.seek(SeekFrom::Current(0)));
+        let size = try!(stream.seek(SeekFrom::End(0)));
+
+        try!(stream.seek(SeekFrom::Start(cur)));
+
+        Ok(size)
+    }
+}
+
+/// Simple `File`-based `IOHandler`
+pub struct DefaultIOHandler;
+
+impl IOHandler<File> for DefaultIOHandler {
+    fn open<P: AsRef<Path>>(&self, path: P, mode: OpenMode) -> io::Result<File> {
+        mode.options().open(path)
+    }
+
+    fn close(&self, _: File) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Callback-based `IOHandler`, where the callback is invoked on `open`
+///
+/// `close` does nothing, and streams should be properly closed on `drop`
+///
+/// The callback may borrow data, such as in-memory buffers, for the lifetime `'a`.
+/// It must be `Send`, since handlers are `Send + Sync`.
+pub struct CallbackIOHandler<'a, S: IOStream> {
+    callback: Mutex<Box<FnMut(&Path, OpenMode) -> io::Result<S> + Send + 'a>>,
+}
+
+impl<'a, S: IOStream> CallbackIOHandler<'a, S> {
+    pub fn new<F>(cb: F) -> CallbackIOHandler<'a, S> where F: FnMut(&Path, OpenMode) -> io::Result<S> + Send + 'a {
+        CallbackIOHandler { callback: Mutex::new(Box::new(cb)) }
+    }
+}
+
+impl<'a, S: IOStream> IOHandler<S> for CallbackIOHandler<'a, S> {
+    fn open<P: AsRef<Path>>(&self, path: P, mode: OpenMode) -> io::Result<S> {
+        if let Ok(mut cb) = self.callback.lock() {
+            // Invoke the callback
+            (&mut *cb)(path.as_ref(), mode)
+        } else {
+            Err(io::Error::new(io::ErrorKind::Other, "PoisonError"))
+        }
+    }
+
+    fn close(&self, _: S) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Failures recorded within the callbacks, to be reported once Assimp returns
+#[derive(Default)]
+struct Failures {
+    error: Option<io::Error>,
+    panic: Option<Box<Any + Send>>,
+}
+
+/// State owned by a `CustomIO` instance and pointed to by its `AiFileIO` user data
+struct IOState<H> {
+    handler: H,
+    failures: Failures,
+}
+
+/// A stream opened by a `CustomIO` instance, along with pointers to the handler which opened it
+/// and to where failures should be recorded
+struct IOFile<S, H> {
+    stream: S,
+    handler: *const H,
+    failures: *mut Failures,
+}
+
+/// Represents some custom input/output system for use with Assimp
+pub struct CustomIO<S, H> where S: IOStream, H: IOHandler<S> {
+    io: AiFileIO,
+    _types: PhantomData<(S, H)>,
+}
+
+impl<S, H> AssimpIO for CustomIO<S, H> where S: IOStream, H: IOHandler<S> {
+    /// Gets a reference to the `AiFileIO` handler
+    fn get(&mut self) -> &mut AiFileIO {
+        &mut self.io
+    }
+
+    fn take_error(&mut self) -> Option<io::Error> {
+        self.state().failures.error.take()
+    }
+
+    fn take_panic(&mut self) -> Option<Box<Any + Send>> {
+        self.state().failures.panic.take()
+    }
+}
+
+impl<S, H> Drop for CustomIO<S, H> where S: IOStream, H: IOHandler<S> {
+    fn drop(&mut self) {
+        assert!(!self.io.user_data.is_null());
+
+        // Convert user_data back into the state to drop it
+        unsafe { Box::from_raw(self.io.user_data as *mut IOState<H>); }
+    }
+}
+
+impl<S, H> CustomIO<S, H> where S: IOStream, H: IOHandler<S> {
+    /// Create a new `CustomIO` instance using the given `IOHandler`
+    pub fn new(handler: H) -> CustomIO<S, H> {
+        CustomIO {
+            io: AiFileIO {
+                open: procs::open_proc::<S, H>,
+                close: procs::close_proc::<S, H>,
+                user_data: Box::into_raw(Box::new(IOState {
+                    handler: handler,
+                    failures: Failures::default(),
+                })) as AiUserData,
+            },
+            _types: PhantomData,
+        }
+    }
+
+    /// Get a reference to the `IOHandler`, such as to inspect it after an import
+    #[inline]
+    pub fn handler(&self) -> &H {
+        unsafe { &(*(self.io.user_data as *const IOState<H>)).handler }
+    }
+
+    #[inline(always)]
+    fn state(&mut self) -> &mut IOState<H> {
+        unsafe { &mut *(self.io.user_data as *mut IOState<H>) }
+    }
+}
+
+impl Default for CustomIO<File, DefaultIOHandler> {
+    /// Create a default `File`-based `CustomIO` instance
+    fn default() -> CustomIO<File, DefaultIOHandler> {
+        CustomIO::new(DefaultIOHandler)
+    }
+}
+
+impl<'a, S: IOStream> CustomIO<S, CallbackIOHandler<'a, S>> {
+    /// Create a new `CustomIO` with a `CallbackIOHandler` directly from a provided callback.
+    pub fn callback<F>(cb: F) -> CustomIO<S, CallbackIOHandler<'a, S>> where F: FnMut(&Path, OpenMode) -> io::Result<S> + Send + 'a {
+        CustomIO::new(CallbackIOHandler::new(cb))
+    }
+}
+
+impl<'a, R: Seek + Read> CustomIO<ReadOnlyStream<R>, CallbackIOHandler<'a, ReadOnlyStream<R>>> {
+    /// Create a new `CustomIO` for read-only streams directly from a provided callback.
+    ///
+    /// Streams returned by the callback are wrapped in a `ReadOnlyStream`.
+    /// Requests to open files for writing are refused without invoking the callback.
+    pub fn read_only<F>(mut cb: F) -> CustomIO<ReadOnlyStream<R>, CallbackIOHandler<'a, ReadOnlyStream<R>>> where F: FnMut(&Path) -> io::Result<R> + Send + 'a {
+        CustomIO::callback(move |path, mode| {
+            if mode.is_write() {
+                Err(io::Error::new(io::ErrorKind::PermissionDenied, "Stream is read-only"))
+            } else {
+                cb(path).map(ReadOnlyStream::new)
+            }
+        })
+    }
+}
+
+mod procs {
+    use libc::{c_char, size_t, c_int};
+
+    use std::io::{self, SeekFrom};
+    use std::panic::{self, AssertUnwindSafe};
+    use std::path::Path;
+    use std::ffi::CStr;
+    use std::ptr;
+    use std::slice;
+
+    use ::ffi::{self, AiFile, AiFileIO, AiUserData};
+
+    use super::{IOStream, IOHandler, IOState, IOFile, Failures, OpenMode};
+
+    /// Stores the error in the owning `CustomIO` instance, unless an earlier error is already there
+    fn store_error(failures: *mut Failures, err: io::Error) {
+        let failures = unsafe { &mut *failures };
+
+        if failures.error.is_none() {
+            failures.error = Some(err);
+        }
+    }
+
+    /// Runs the body of a callback, catching any panic so that it doesn't unwind into Assimp.
+    ///
+    /// If a panic is caught, its payload is stored and `failed` is returned instead.
+    fn guard<R, F>(failures: *mut Failures, failed: R, f: F) -> R where F: FnOnce() -> R {
+        match panic::catch_unwind(AssertUnwindSafe(f)) {
+            Ok(res) => res,
+            Err(payload) => {
+                let failures = unsafe { &mut *failures };
+
+                if failures.panic.is_none() {
+                    failures.panic = Some(payload);
+                }
+
+                failed
+            }
+        }
+    }
+
+    /// Opens a new file via the given handler and boxes it up (and subsequently turns it into a raw pointer) to be used later
+    ///
+    /// By using template parameters on the procedures, we don't need to fool around with trait objects and double boxing
+    #[inline(never)]
+    pub extern "C" fn open_proc<S, H>(file_io: *mut AiFileIO, path: *const c_char, mode: *const c_char) -> *mut AiFile where S: IOStream, H: IOHandler<S> {
+        let state: &mut IOState<H> = user_data!(file_io);
+
+        c_assert!(!path.is_null());
+
+        let path = if let Ok(path) = unsafe { CStr::from_ptr(path).to_str() } { Path::new(path) } else {
+            return ptr::null_mut();
+        };
+
+        // Assume reading if no mode was given at all
+        let mode = if mode.is_null() { OpenMode::Read } else {
+            match unsafe { CStr::from_ptr(mode).to_str() }.ok().and_then(OpenMode::from_c_mode) {
+                Some(mode) => mode,
+                None => return ptr::null_mut(),
+            }
+        };
+
+        let failures = &mut state.failures as *mut Failures;
+        let handler = &state.handler;
+
+        // Failing to open a file is not an error by itself, since Assimp probes for files that may not exist
+        let stream: S = if let Some(Ok(stream)) = guard(failures, None, || Some(handler.open(path, mode))) { stream } else {
+            return ptr::null_mut();
+        };
+
+        Box::into_raw(Box::new(AiFile {
+            user_data: Box::into_raw(Box::new(IOFile {
+                stream: stream,
+                handler: handler as *const H,
+                failures: failures,
+            })) as AiUserData,
+            read: read_proc::<S, H>,
+            write: write_proc::<S, H>,
+            tell: tell_proc::<S, H>,
+            size: tell_size_proc::<S, H>,
+            seek: seek_proc::<S, H>,
+            flush: flush_proc::<S, H>,
+        }))
+    }
+
+    /// Closes a stream by unboxing it and passing it to the handler's close function, which usually just drops it
+    #[inline(never)]
+    pub extern "C" fn close_proc<S, H>(file_io: *mut AiFileIO, file: *mut AiFile) where S: IOStream, H: IOHandler<S> {
+        let state: &mut IOState<H> = user_data!(file_io);
+
+        c_assert!(!file.is_null());
+
+        let file = unsafe { Box::from_raw(file) };
+
+        c_assert!(!file.user_data.is_null());
+
+        let file = unsafe { Box::from_raw(file.user_data as *mut IOFile<S, H>) };
+
+        let failures = &mut state.failures as *mut Failures;
+        let handler = &state.handler;
+
+        guard(failures, (), move || {
+            if let Err(err) = handler.close(file.stream) {
+                store_error(failures, err);
+            }
+        })
+    }
+
+    /// Reads up to `count` elements of `size` bytes from the stream into the given buffer,
+    /// returning the number of whole elements read, like `fread`.
+    ///
+    /// Streams may return fewer bytes than requested from a single read, so this reads
+    /// until the buffer is full or the end of the stream is reached.
+    #[inline(never)]
+    pub extern "C" fn read_proc<S, H>(file: *mut AiFile, buffer: *mut c_char, size: size_t, count: size_t) -> size_t where S: IOStream, H: IOHandler<S> {
+        c_assert!(!buffer.is_null());
+
+        let file: &mut IOFile<S, H> = user_data!(file);
+
+        if size == 0 {
+            return 0;
+        }
+
+        let buffer = unsafe { slice::from_raw_parts_mut(buffer as *mut u8, size as usize * count as usize) };
+
+        guard(file.failures, 0, || {
+            let mut amt = 0;
+
+            while amt < buffer.len() {
+                match file.stream.read(&mut buffer[amt..]) {
+                    Ok(0) => break,
+                    Ok(n) => amt += n,
+                    Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {}
+                    Err(err) => {
+                        store_error(file.failures, err);
+
+                        break;
+                    }
+                }
+            }
+
+            (amt / size as usize) as size_t
+        })
+    }
+
+    /// Writes some bytes to the stream, returning the number of bytes written.
+    #[inline(never)]
+    pub extern "C" fn write_proc<S, H>(file: *mut AiFile, buffer: *const c_char, size: size_t, count: size_t) -> size_t where S: IOStream, H: IOHandler<S> {
+        c_assert!(!buffer.is_null());
+
+        let file: &mut IOFile<S, H> = user_data!(file);
+
+        let buffer = unsafe { slice::from_raw_parts(buffer as *const u8, size as usize * count as usize) };
+
+        guard(file.failures, 0, || match file.stream.write(buffer) {
+            Ok(amt) => amt as size_t,
+            Err(err) => {
+                store_error(file.failures, err);
+
+                0
+            }
+        })
+    }
+
+    /// Gets the current position of the stream
+    #[inline(never)]
+    pub extern "C" fn tell_proc<S, H>(file: *mut AiFile) -> size_t where S: IOStream, H: IOHandler<S> {
+        let file: &mut IOFile<S, H> = user_data!(file);
+
+        guard(file.failures, 0, || match file.stream.seek(SeekFrom::Current(0)) {
+            Ok(pos) => pos as size_t,
+            Err(err) => {
+                store_error(file.failures, err);
+
+                0
+            }
+        })
+    }
+
+    /// Determine the overall length of the stream through the handler which opened it
+    #[inline(never)]
+    pub extern "C" fn tell_size_proc<S, H>(file: *mut AiFile) -> size_t where S: IOStream, H: IOHandler<S> {
+        let file: &mut IOFile<S, H> = user_data!(file);
+
+        let handler = unsafe { &*file.handler };
+
+        guard(file.failures, 0, || match handler.size(&mut file.stream) {
+            Ok(size) => size as size_t,
+            Err(err) => {
+                store_error(file.failures, err);
+
+                0
+            }
+        })
+    }
+
+    /// Seeks to a position in the stream. `S` must be `Seek`, so this is easy.
+    #[inline(never)]
+    pub extern "C" fn seek_proc<S, H>(file: *mut AiFile, pos: size_t, origin: c_int) -> c_int where S: IOStream, H: IOHandler<S> {
+        let file: &mut IOFile<S, H> = user_data!(file);
+
+        let origin = match origin {
+            ffi::AI_ORIGIN_SET => SeekFrom::Start(pos as u64),
+            ffi::AI_ORIGIN_CUR => SeekFrom::Current(pos as i64),
+            ffi::AI_ORIGIN_END => SeekFrom::End(pos as i64),
+            _ => {
+                store_error(file.failures, io::Error::new(io::ErrorKind::InvalidInput, "Invalid Seek origin"));
+
+                return ffi::AI_FAILURE;
+            }
+        };
+
+        // This procedure allows returning success and failure values
+        guard(file.failures, ffi::AI_FAILURE, || match file.stream.seek(origin) {
+            Ok(_) => ffi::AI_SUCCESS,
+            Err(err) => {
+                store_error(file.failures, err);
+
+                ffi::AI_FAILURE
+            }
+        })
+    }
+
+    /// Simply flushes the stream
+    #[inline(never)]
+    pub extern "C" fn flush_proc<S, H>(file: *mut AiFile) where S: IOStream, H: IOHandler<S> {
+        let file: &mut IOFile<S, H> = user_data!(file);
+
+        guard(file.failures, (), || {
+            if let Err(err) = file.stream.flush() {
+                store_error(file.failures, err);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ffi::CString;
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Reads at most one byte at a time
+    struct Trickle(Cursor<Vec<u8>>);
+
+    impl Read for Trickle {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(1);
+
+            self.0.read(&mut buf[..len])
+        }
+    }
+
+    impl Seek for Trickle {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.0.seek(pos)
+        }
+    }
+
+    #[test]
+    fn test_read_proc() {
+        let mut io = CustomIO::read_only(|_| Ok(Trickle(Cursor::new(vec![1, 2, 3, 4, 5, 6, 7]))));
+
+        let path = CString::new("model.obj").unwrap();
+        let mode = CString::new("rb").unwrap();
+
+        let file_io = io.get() as *mut AiFileIO;
+
+        unsafe {
+            let file = ((*file_io).open)(file_io, path.as_ptr(), mode.as_ptr());
+
+            assert!(!file.is_null());
+
+            let mut buffer = [0u8; 8];
+
+            // Only whole elements count, like with `fread`
+            assert_eq!(((*file).read)(file, buffer.as_mut_ptr() as *mut _, 2, 4), 3);
+            assert_eq!(&buffer[..7], &[1, 2, 3, 4, 5, 6, 7]);
+            assert_eq!(((*file).read)(file, buffer.as_mut_ptr() as *mut _, 1, 8), 0);
+
+            ((*file_io).close)(file_io, file);
+        }
+
+        assert!(io.take_error().is_none());
+    }
+
+    #[test]
+    fn test_size_queries() {
+        let handler = InstrumentedIOHandler::new(CallbackIOHandler::new(|_, _| Ok(Cursor::new(vec![0u8; 10]))));
+
+        let mut io = CustomIO::new(handler);
+
+        let path = CString::new("model.obj").unwrap();
+        let mode = CString::new("rb").unwrap();
+
+        let file_io = io.get() as *mut AiFileIO;
+
+        unsafe {
+            let file = ((*file_io).open)(file_io, path.as_ptr(), mode.as_ptr());
+
+            assert!(!file.is_null());
+
+            assert_eq!(((*file).size)(file), 10);
+            assert_eq!(((*file).seek)(file, 4, ::ffi::AI_ORIGIN_SET), ::ffi::AI_SUCCESS);
+            assert_eq!(((*file).size)(file), 10);
+            assert_eq!(((*file).tell)(file), 4);
+
+            ((*file_io).close)(file_io, file);
+        }
+
+        let stats = io.handler().stats();
+
+        assert_eq!(stats.len(), 1);
+        assert_eq!((stats[0].size_queries, stats[0].seeks, stats[0].tells), (2, 1, 1));
+    }
+
+    #[test]
+    fn test_open_mode() {
+        assert_eq!(OpenMode::from_c_mode("rb"), Some(OpenMode::Read));
+        assert_eq!(OpenMode::from_c_mode("wt"), Some(OpenMode::Write));
+        assert_eq!(OpenMode::from_c_mode("a"), Some(OpenMode::Append));
+        assert_eq!(OpenMode::from_c_mode("r+b"), Some(OpenMode::ReadUpdate));
+        assert_eq!(OpenMode::from_c_mode("wb+"), Some(OpenMode::WriteUpdate));
+        assert_eq!(OpenMode::from_c_mode("x"), None);
+        assert_eq!(OpenMode::from_c_mode(""), None);
+
+        assert!(OpenMode::Read.is_read() && !OpenMode::Read.is_write());
+        assert!(!OpenMode::Write.is_read() && OpenMode::Write.is_write());
+        assert!(OpenMode::AppendUpdate.is_read() && OpenMode::AppendUpdate.is_write());
+    }
+}
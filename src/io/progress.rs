@@ -0,0 +1,219 @@
+//! Progress reporting and cancellation for any `IOHandler`
+
+use std::path::Path;
+use std::io::prelude::*;
+use std::io::{self, SeekFrom};
+use std::sync::{Arc, Mutex};
+
+use super::{IOStream, IOHandler, OpenMode};
+
+/// Progress of an import, estimated from the number of bytes read versus the size of every opened stream
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// Total number of bytes read from all streams
+    pub bytes_read: u64,
+    /// Total size of all streams opened for reading so far
+    pub bytes_total: u64,
+}
+
+impl Progress {
+    /// Estimated fraction of the import done, from `0.0` to `1.0`
+    pub fn fraction(&self) -> f32 {
+        if self.bytes_total == 0 { 0.0 } else {
+            (self.bytes_read as f64 / self.bytes_total as f64).min(1.0) as f32
+        }
+    }
+}
+
+/// Returned by progress callbacks to decide whether the import should go on
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ProgressAction {
+    Continue,
+    Cancel,
+}
+
+/// State shared between a `ProgressIOHandler` and all of its streams
+struct Tracker<'a> {
+    progress: Mutex<(Progress, bool)>,
+    callback: Mutex<Box<FnMut(Progress) -> ProgressAction + Send + 'a>>,
+}
+
+impl<'a> Tracker<'a> {
+    fn is_cancelled(&self) -> bool {
+        match self.progress.lock() {
+            Ok(progress) => progress.1,
+            Err(poisoned) => poisoned.into_inner().1,
+        }
+    }
+
+    /// Updates the progress and invokes the callback, returning `false` if the import should be cancelled
+    fn update<F>(&self, f: F) -> bool where F: FnOnce(&mut Progress) {
+        let progress = {
+            let mut state = match self.progress.lock() {
+                Ok(state) => state,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+
+            if state.1 {
+                return false;
+            }
+
+            f(&mut state.0);
+
+            state.0
+        };
+
+        let action = match self.callback.lock() {
+            Ok(mut cb) => (&mut *cb)(progress),
+            Err(_) => ProgressAction::Cancel,
+        };
+
+        if action == ProgressAction::Cancel {
+            match self.progress.lock() {
+                Ok(mut state) => state.1 = true,
+                Err(poisoned) => poisoned.into_inner().1 = true,
+            }
+
+            false
+        } else {
+            true
+        }
+    }
+}
+
+fn cancelled_error() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "Import cancelled")
+}
+
+/// Stream created by `ProgressIOHandler`, which reports every read
+///
+/// Once the import has been cancelled, all reads fail.
+pub struct ProgressStream<'a, S: IOStream> {
+    inner: S,
+    tracker: Arc<Tracker<'a>>,
+}
+
+impl<'a, S: IOStream> ProgressStream<'a, S> {
+    #[inline]
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<'a, S: IOStream> Read for ProgressStream<'a, S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.tracker.is_cancelled() {
+            return Err(cancelled_error());
+        }
+
+        let amt = try!(self.inner.read(buf));
+
+        if self.tracker.update(|progress| progress.bytes_read += amt as u64) {
+            Ok(amt)
+        } else {
+            Err(cancelled_error())
+        }
+    }
+}
+
+impl<'a, S: IOStream> Write for ProgressStream<'a, S> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<'a, S: IOStream> Seek for ProgressStream<'a, S> {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+/// `IOHandler` which wraps another handler and reports the progress of an import to a callback.
+///
+/// The callback is invoked whenever a file is opened for reading or read from, and may cancel the import,
+/// after which every read fails. Note that many importers read entire files at once before parsing them,
+/// so progress can be coarse and cancellation only takes effect on the next read.
+pub struct ProgressIOHandler<'a, H> {
+    inner: H,
+    tracker: Arc<Tracker<'a>>,
+}
+
+impl<'a, H> ProgressIOHandler<'a, H> {
+    /// Wrap the given handler, reporting progress to the given callback
+    pub fn new<F>(inner: H, cb: F) -> ProgressIOHandler<'a, H> where F: FnMut(Progress) -> ProgressAction + Send + 'a {
+        ProgressIOHandler {
+            inner: inner,
+            tracker: Arc::new(Tracker {
+                progress: Mutex::new((Progress { bytes_read: 0, bytes_total: 0 }, false)),
+                callback: Mutex::new(Box::new(cb)),
+            }),
+        }
+    }
+
+    #[inline]
+    pub fn get_ref(&self) -> &H {
+        &self.inner
+    }
+
+    /// Checks if the callback has cancelled the import
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.tracker.is_cancelled()
+    }
+
+    /// Gets the last reported progress
+    pub fn progress(&self) -> Progress {
+        match self.tracker.progress.lock() {
+            Ok(state) => state.0,
+            Err(poisoned) => poisoned.into_inner().0,
+        }
+    }
+}
+
+impl<'a, S, H> IOHandler<ProgressStream<'a, S>> for ProgressIOHandler<'a, H> where S: IOStream, H: IOHandler<S> {
+    fn open<P: AsRef<Path>>(&self, path: P, mode: OpenMode) -> io::Result<ProgressStream<'a, S>> {
+        if self.tracker.is_cancelled() {
+            return Err(cancelled_error());
+        }
+
+        let mut inner = try!(self.inner.open(path, mode));
+
+        if mode.is_read() {
+            let cur = try!(inner.seek(SeekFrom::Current(0)));
+            let size = try!(self.inner.size(&mut inner));
+
+            if !self.tracker.update(|progress| progress.bytes_total += size.saturating_sub(cur)) {
+                try!(self.inner.close(inner));
+
+                return Err(cancelled_error());
+            }
+        }
+
+        Ok(ProgressStream {
+            inner: inner,
+            tracker: self.tracker.clone(),
+        })
+    }
+
+    #[inline]
+    fn close(&self, stream: ProgressStream<'a, S>) -> io::Result<()> {
+        self.inner.close(stream.inner)
+    }
+
+    #[inline]
+    fn size(&self, stream: &mut ProgressStream<'a, S>) -> io::Result<u64> {
+        self.inner.size(&mut stream.inner)
+    }
+}
@@ -8,6 +8,8 @@ extern crate lazy_static;
 #[macro_use]
 extern crate enum_primitive;
 extern crate vec_map;
+#[macro_use]
+extern crate log;
 
 #[macro_use]
 extern crate trace_error;
@@ -15,6 +17,19 @@ extern crate trace_error;
 #[cfg(feature = "compat")]
 extern crate nalgebra;
 
+#[cfg(feature = "mmap")]
+extern crate memmap;
+
+#[cfg(feature = "settings")]
+extern crate serde;
+#[cfg(feature = "settings")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(feature = "settings")]
+extern crate serde_json;
+#[cfg(feature = "settings")]
+extern crate toml;
+
 #[cfg(feature = "compat")]
 pub mod compat;
 
@@ -30,9 +45,21 @@ pub mod components;
 pub mod scene;
 pub mod formats;
 pub mod io;
+pub mod logging;
+pub mod properties;
+pub mod options;
+pub mod importer;
+pub mod library;
+pub mod pipeline;
+pub mod profile;
+#[cfg(feature = "settings")]
+pub mod settings;
 
 pub use ffi::{AiVector3D, AiVector2D, AiMatrix3x3, AiMatrix4x4};
 pub use error::*;
 pub use postprocess::PostprocessEffect;
 pub use components::*;
-pub use scene::Scene;
\ No newline at end of file
+pub use scene::Scene;
+pub use importer::Importer;
+pub use library::{version, LibraryInfo};
+pub use pipeline::{Pipeline, PostprocessStep};
\ No newline at end of file
@@ -0,0 +1,130 @@
+//! Information about the Assimp library linked at runtime
+
+use libc::{c_char, c_uint};
+
+use std::ffi::CStr;
+use std::fmt;
+
+use ffi;
+use error::*;
+
+bitflags! {
+    /// Flags Assimp was compiled with
+    pub flags CompileFlags: c_uint {
+        const SHARED = ffi::ASSIMP_CFLAGS_SHARED,
+        const STLPORT = ffi::ASSIMP_CFLAGS_STLPORT,
+        const DEBUG = ffi::ASSIMP_CFLAGS_DEBUG,
+        const NO_BOOST = ffi::ASSIMP_CFLAGS_NOBOOST,
+        const SINGLE_THREADED = ffi::ASSIMP_CFLAGS_SINGLETHREADED,
+        const DOUBLE_PRECISION = ffi::ASSIMP_CFLAGS_DOUBLE_SUPPORT
+    }
+}
+
+/// Version and build information of the linked Assimp library
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LibraryInfo {
+    pub major: u32,
+    pub minor: u32,
+    pub revision: u32,
+    pub flags: CompileFlags,
+    /// Git branch Assimp was built from, which is empty if unknown
+    pub branch: String,
+    pub legal: String,
+}
+
+impl LibraryInfo {
+    /// Checks that the linked library was built with the same floating point precision
+    /// as the `ffi` structures, which depends on the `double_precision` feature,
+    /// and that its version matches the structure layouts selected by the version features:
+    ///
+    /// * `assimp4_1` for Assimp 4.1 and newer, which add names and weights to anim meshes
+    /// * `assimp5_0` for Assimp 5.0 and newer, which store string lengths as 32 bits
+    ///   and add morphing methods and bounding boxes to meshes
+    /// * `assimp5_1` for Assimp 5.1 and newer, which add armature data to bones
+    pub fn check_layout(&self) -> AiResult<()> {
+        let double = self.flags.contains(DOUBLE_PRECISION);
+
+        if double != cfg!(feature = "double_precision") {
+            throw!(AiError::Internal(format!("Assimp {} was built {} double precision support, which does not match the `double_precision` feature",
+                                             self, if double { "with" } else { "without" })));
+        }
+
+        let features = [
+            ("assimp4_1", (4, 1), cfg!(feature = "assimp4_1")),
+            ("assimp5_0", (5, 0), cfg!(feature = "assimp5_0")),
+            ("assimp5_1", (5, 1), cfg!(feature = "assimp5_1")),
+        ];
+
+        for &(feature, version, enabled) in &features {
+            let required = (self.major, self.minor) >= version;
+
+            if required != enabled {
+                throw!(AiError::Internal(format!("Assimp {} requires the `{}` feature to be {}",
+                                                 self, feature, if required { "enabled" } else { "disabled" })));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for LibraryInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.revision)
+    }
+}
+
+/// Gets the version and build information of the linked Assimp library
+pub fn version() -> LibraryInfo {
+    fn string(s: *const c_char) -> String {
+        if s.is_null() { String::new() } else {
+            unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned()
+        }
+    }
+
+    unsafe {
+        LibraryInfo {
+            major: ffi::aiGetVersionMajor() as u32,
+            minor: ffi::aiGetVersionMinor() as u32,
+            revision: ffi::aiGetVersionRevision() as u32,
+            flags: CompileFlags::from_bits_truncate(ffi::aiGetCompileFlags()),
+            branch: string(ffi::aiGetBranchName()),
+            legal: string(ffi::aiGetLegalString()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn info(major: u32, minor: u32) -> LibraryInfo {
+        LibraryInfo {
+            major: major,
+            minor: minor,
+            revision: 0,
+            flags: if cfg!(feature = "double_precision") { DOUBLE_PRECISION } else { CompileFlags::empty() },
+            branch: String::new(),
+            legal: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_check_layout() {
+        let supported = if cfg!(feature = "assimp5_1") {
+            (5, 1)
+        } else if cfg!(feature = "assimp5_0") {
+            (5, 0)
+        } else if cfg!(feature = "assimp4_1") {
+            (4, 1)
+        } else {
+            (4, 0)
+        };
+
+        for &version in &[(4, 0), (4, 1), (5, 0), (5, 1), (5, 2)] {
+            let matching = version == supported || (supported == (5, 1) && version > supported);
+
+            assert_eq!(info(version.0, version.1).check_layout().is_ok(), matching, "Assimp {}.{}", version.0, version.1);
+        }
+    }
+}
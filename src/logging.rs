@@ -0,0 +1,251 @@
+//! Routes Assimp's log output into the `log` crate
+//!
+//! Messages are logged with the `assimp` target, at the level Assimp gave them.
+//! Debug messages are only emitted by Assimp when verbose logging is enabled.
+//! Messages are also collected by every `LogCapture` active on the thread which emitted them.
+
+use libc::c_char;
+
+use std::cell::{Cell, RefCell};
+use std::ffi::CStr;
+use std::marker::PhantomData;
+use std::panic;
+use std::ptr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use log::LogLevel;
+
+use ffi;
+
+/// Target used for all messages forwarded from Assimp
+pub const LOG_TARGET: &'static str = "assimp";
+
+/// Prefix of the messages emitted by the `VALIDATE_DATA_STRUCTURE` step for non-fatal problems
+const VALIDATION_WARNING_PREFIX: &'static str = "Validation warning:";
+
+lazy_static! {
+    static ref ATTACHED: Mutex<bool> = Mutex::new(false);
+}
+
+/// Whether messages are forwarded to the `log` crate
+static FORWARD: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Message buffers of the active captures on this thread, by capture id
+    static CAPTURES: RefCell<Vec<(usize, Vec<LogMessage>)>> = RefCell::new(Vec::new());
+
+    static NEXT_CAPTURE_ID: Cell<usize> = Cell::new(0);
+}
+
+/// Attaches the single log stream used by this crate, if it isn't already.
+///
+/// Assimp identifies log streams by their callback, so attaching and detaching a stream per import
+/// would detach the streams of imports on other threads. Instead, one stream stays attached
+/// and dispatches every message to the captures of the thread it was emitted on.
+fn ensure_attached() {
+    let mut attached = ATTACHED.lock().unwrap();
+
+    if !*attached {
+        let stream = ffi::AiLogStream {
+            callback: dispatch_proc,
+            user: ptr::null_mut(),
+        };
+
+        unsafe { ffi::aiAttachLogStream(&stream) };
+
+        *attached = true;
+    }
+}
+
+/// Splits a message from Assimp's default logger, such as `"Warn,  T0: Some warning\n"`,
+/// into its level and the message itself.
+///
+/// Messages without a recognized prefix are treated as `Info`.
+pub fn parse_message(message: &str) -> (LogLevel, &str) {
+    let (level, rest) = match message.find(',') {
+        Some(index) => {
+            let level = match &message[..index] {
+                "Debug" | "Verbose" => Some(LogLevel::Debug),
+                "Info" => Some(LogLevel::Info),
+                "Warn" => Some(LogLevel::Warn),
+                "Error" => Some(LogLevel::Error),
+                _ => None,
+            };
+
+            match level {
+                Some(level) => (level, message[index + 1..].trim_left()),
+                None => (LogLevel::Info, message),
+            }
+        }
+        None => (LogLevel::Info, message),
+    };
+
+    // Strip the thread identifier, like `T0: `
+    let rest = match rest.find(": ") {
+        Some(index) if rest.starts_with('T') && rest[1..index].chars().all(|c| c.is_digit(10)) => &rest[index + 2..],
+        _ => rest,
+    };
+
+    (level, rest.trim_right())
+}
+
+extern "C" fn dispatch_proc(message: *const c_char, _: *mut c_char) {
+    if message.is_null() {
+        return;
+    }
+
+    // Loggers are user code, and the capture buffers may fail to borrow or grow,
+    // so don't let any of it unwind into Assimp
+    let _ = panic::catch_unwind(|| {
+        let message = unsafe { CStr::from_ptr(message) }.to_string_lossy();
+
+        let (level, message) = parse_message(&message);
+
+        // Assimp emits messages on the thread doing the work, so they belong to the captures of this thread
+        CAPTURES.with(|captures| {
+            for &mut (_, ref mut messages) in captures.borrow_mut().iter_mut() {
+                messages.push(LogMessage { level: level, message: message.to_string() });
+            }
+        });
+
+        if FORWARD.load(Ordering::SeqCst) {
+            log!(target: LOG_TARGET, level, "{}", message);
+        }
+    });
+}
+
+/// Forwards all messages emitted by Assimp to the `log` crate.
+///
+/// Does nothing if messages are already forwarded.
+pub fn attach() {
+    FORWARD.store(true, Ordering::SeqCst);
+
+    ensure_attached();
+}
+
+/// Stops forwarding messages to the `log` crate.
+///
+/// The underlying log stream stays attached, since `LogCapture` uses it as well.
+pub fn detach() {
+    FORWARD.store(false, Ordering::SeqCst);
+}
+
+/// A single message emitted by Assimp
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogMessage {
+    pub level: LogLevel,
+    pub message: String,
+}
+
+/// Messages emitted by Assimp while capturing, such as during a single import
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportLog {
+    pub messages: Vec<LogMessage>,
+}
+
+impl ImportLog {
+    /// Returns an iterator to all warnings in the log
+    pub fn warnings<'a>(&'a self) -> Box<Iterator<Item = &'a LogMessage> + 'a> {
+        Box::new(self.messages.iter().filter(|msg| msg.level == LogLevel::Warn))
+    }
+
+    /// Returns an iterator to all errors in the log
+    pub fn errors<'a>(&'a self) -> Box<Iterator<Item = &'a LogMessage> + 'a> {
+        Box::new(self.messages.iter().filter(|msg| msg.level == LogLevel::Error))
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Gets the individual warnings emitted by the `VALIDATE_DATA_STRUCTURE` step, without their prefix
+    pub fn validation_warnings(&self) -> Vec<String> {
+        self.warnings().filter_map(|msg| {
+            if msg.message.starts_with(VALIDATION_WARNING_PREFIX) {
+                Some(msg.message[VALIDATION_WARNING_PREFIX.len()..].trim().to_string())
+            } else {
+                None
+            }
+        }).collect()
+    }
+
+    /// Gets the last error in the log, which is the reason a failed import or postprocessing step gives
+    pub fn last_error(&self) -> Option<&LogMessage> {
+        self.messages.iter().rev().find(|msg| msg.level == LogLevel::Error)
+    }
+}
+
+/// Captures messages emitted by Assimp on the current thread from the time it is started
+/// until it is finished or dropped.
+///
+/// Assimp imports files on the calling thread, so this captures exactly the messages of imports
+/// made on this thread, even while other threads are importing.
+pub struct LogCapture {
+    id: usize,
+    // Bound to the thread whose messages it captures
+    _thread: PhantomData<*const ()>,
+}
+
+impl LogCapture {
+    /// Starts capturing messages emitted on the current thread
+    pub fn start() -> LogCapture {
+        ensure_attached();
+
+        let id = NEXT_CAPTURE_ID.with(|next| {
+            let id = next.get();
+
+            next.set(id.wrapping_add(1));
+
+            id
+        });
+
+        CAPTURES.with(|captures| captures.borrow_mut().push((id, Vec::new())));
+
+        LogCapture { id: id, _thread: PhantomData }
+    }
+
+    /// Stops capturing and returns all messages captured
+    pub fn finish(mut self) -> ImportLog {
+        ImportLog { messages: self.take() }
+    }
+
+    fn take(&mut self) -> Vec<LogMessage> {
+        CAPTURES.with(|captures| {
+            let mut captures = captures.borrow_mut();
+
+            match captures.iter().position(|&(id, _)| id == self.id) {
+                Some(index) => captures.remove(index).1,
+                None => Vec::new(),
+            }
+        })
+    }
+}
+
+impl Drop for LogCapture {
+    fn drop(&mut self) {
+        self.take();
+    }
+}
+
+/// Enables or disables verbose logging, which includes debug messages.
+pub fn set_verbose(enable: bool) {
+    unsafe { ffi::aiEnableVerboseLogging(if enable { ffi::TRUE } else { ffi::FALSE }) }
+}
+
+#[cfg(test)]
+mod test {
+    use log::LogLevel;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_message() {
+        assert_eq!(parse_message("Warn,  T0: Skipping one or more lines\n"), (LogLevel::Warn, "Skipping one or more lines"));
+        assert_eq!(parse_message("Error, T12: Failed to open file"), (LogLevel::Error, "Failed to open file"));
+        assert_eq!(parse_message("Debug, T0: Collada: 1 meshes"), (LogLevel::Debug, "Collada: 1 meshes"));
+        assert_eq!(parse_message("Info,  T0: Load sphere.dae"), (LogLevel::Info, "Load sphere.dae"));
+        assert_eq!(parse_message("Something else"), (LogLevel::Info, "Something else"));
+    }
+}
@@ -1,5 +1,7 @@
 macro_rules! c_abort {
     ($($fmt:expr),*) => {{
+        use ::std::io::Write;
+
         let _ = writeln!(&mut ::std::io::stderr(), $($fmt),*);
 
         let _ = ::std::io::stderr().flush();
@@ -0,0 +1,197 @@
+//! Typed options for specific importers, which map to their `AI_CONFIG_*` import properties
+
+use properties::*;
+
+/// Options for an importer, which can be set as import properties
+pub trait ImporterOptions {
+    /// Sets the import properties corresponding to the options
+    fn apply(&self, properties: &mut ImportProperties);
+
+    /// Gets the extensions of auxiliary files to skip, for options Assimp has no property for.
+    ///
+    /// `Importer` refuses to open files with these extensions, as if they didn't exist.
+    fn ignored_extensions(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+}
+
+/// Options for the FBX importer, defaulting to Assimp's defaults
+#[derive(Clone, Debug, PartialEq)]
+pub struct FbxOptions {
+    pub read_all_geometry_layers: bool,
+    pub read_all_materials: bool,
+    pub read_materials: bool,
+    pub read_textures: bool,
+    pub read_cameras: bool,
+    pub read_lights: bool,
+    pub read_animations: bool,
+    pub strict_mode: bool,
+    pub preserve_pivots: bool,
+    pub optimize_empty_animation_curves: bool,
+}
+
+impl Default for FbxOptions {
+    fn default() -> FbxOptions {
+        FbxOptions {
+            read_all_geometry_layers: true,
+            read_all_materials: false,
+            read_materials: true,
+            read_textures: true,
+            read_cameras: true,
+            read_lights: true,
+            read_animations: true,
+            strict_mode: false,
+            preserve_pivots: true,
+            optimize_empty_animation_curves: true,
+        }
+    }
+}
+
+impl ImporterOptions for FbxOptions {
+    fn apply(&self, properties: &mut ImportProperties) {
+        properties.set_bool(AI_CONFIG_IMPORT_FBX_READ_ALL_GEOMETRY_LAYERS, self.read_all_geometry_layers)
+                  .set_bool(AI_CONFIG_IMPORT_FBX_READ_ALL_MATERIALS, self.read_all_materials)
+                  .set_bool(AI_CONFIG_IMPORT_FBX_READ_MATERIALS, self.read_materials)
+                  .set_bool(AI_CONFIG_IMPORT_FBX_READ_TEXTURES, self.read_textures)
+                  .set_bool(AI_CONFIG_IMPORT_FBX_READ_CAMERAS, self.read_cameras)
+                  .set_bool(AI_CONFIG_IMPORT_FBX_READ_LIGHTS, self.read_lights)
+                  .set_bool(AI_CONFIG_IMPORT_FBX_READ_ANIMATIONS, self.read_animations)
+                  .set_bool(AI_CONFIG_IMPORT_FBX_STRICT_MODE, self.strict_mode)
+                  .set_bool(AI_CONFIG_IMPORT_FBX_PRESERVE_PIVOTS, self.preserve_pivots)
+                  .set_bool(AI_CONFIG_IMPORT_FBX_OPTIMIZE_EMPTY_ANIMATION_CURVES, self.optimize_empty_animation_curves);
+    }
+}
+
+/// Options for importing OBJ files
+///
+/// OBJ files often lack normals, so these configure how `GEN_SMOOTH_NORMALS` generates them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjOptions {
+    /// Maximum angle between smoothed normals, in degrees
+    pub smoothing_angle: f32,
+    /// Skip the `.mtl` material libraries, giving every mesh the default material.
+    ///
+    /// Assimp has no property for this, so `Importer` refuses to open `.mtl` files instead.
+    pub ignore_materials: bool,
+}
+
+impl Default for ObjOptions {
+    fn default() -> ObjOptions {
+        ObjOptions { smoothing_angle: 175.0, ignore_materials: false }
+    }
+}
+
+impl ImporterOptions for ObjOptions {
+    fn apply(&self, properties: &mut ImportProperties) {
+        properties.set_float(AI_CONFIG_PP_GSN_MAX_SMOOTHING_ANGLE, self.smoothing_angle);
+    }
+
+    fn ignored_extensions(&self) -> Vec<&'static str> {
+        if self.ignore_materials { vec!["mtl"] } else { Vec::new() }
+    }
+}
+
+/// Options for the Collada importer, defaulting to Assimp's defaults
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ColladaOptions {
+    pub ignore_up_direction: bool,
+    pub use_collada_names: bool,
+}
+
+impl ImporterOptions for ColladaOptions {
+    fn apply(&self, properties: &mut ImportProperties) {
+        properties.set_bool(AI_CONFIG_IMPORT_COLLADA_IGNORE_UP_DIRECTION, self.ignore_up_direction)
+                  .set_bool(AI_CONFIG_IMPORT_COLLADA_USE_COLLADA_NAMES, self.use_collada_names);
+    }
+}
+
+/// Options for the glTF importers, defaulting to Assimp's defaults
+///
+/// Assimp has no glTF-specific import properties, so these are general properties
+/// which apply to skinned formats such as glTF.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GltfOptions {
+    /// Don't generate dummy meshes for skeletons without meshes
+    pub no_skeleton_meshes: bool,
+    /// Remove bones without any vertex weights
+    pub remove_empty_bones: bool,
+}
+
+impl Default for GltfOptions {
+    fn default() -> GltfOptions {
+        GltfOptions {
+            no_skeleton_meshes: false,
+            remove_empty_bones: true,
+        }
+    }
+}
+
+impl ImporterOptions for GltfOptions {
+    fn apply(&self, properties: &mut ImportProperties) {
+        properties.set_bool(AI_CONFIG_IMPORT_NO_SKELETON_MESHES, self.no_skeleton_meshes)
+                  .set_bool(AI_CONFIG_IMPORT_REMOVE_EMPTY_BONES, self.remove_empty_bones);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn applied<O: ImporterOptions>(options: &O) -> Vec<(String, String)> {
+        let mut properties = ImportProperties::new();
+
+        options.apply(&mut properties);
+
+        properties.iter().map(|(name, value)| (name.clone(), format!("{:?}", value))).collect()
+    }
+
+    fn expected(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        let mut pairs: Vec<(String, String)> = pairs.iter().map(|&(name, value)| (name.to_string(), value.to_string())).collect();
+
+        pairs.sort();
+        pairs
+    }
+
+    #[test]
+    fn test_fbx_options() {
+        assert_eq!(applied(&FbxOptions { read_lights: false, strict_mode: true, ..FbxOptions::default() }), expected(&[
+            ("IMPORT_FBX_READ_ALL_GEOMETRY_LAYERS", "Integer(1)"),
+            ("IMPORT_FBX_READ_ALL_MATERIALS", "Integer(0)"),
+            ("IMPORT_FBX_READ_MATERIALS", "Integer(1)"),
+            ("IMPORT_FBX_READ_TEXTURES", "Integer(1)"),
+            ("IMPORT_FBX_READ_CAMERAS", "Integer(1)"),
+            ("IMPORT_FBX_READ_LIGHTS", "Integer(0)"),
+            ("IMPORT_FBX_READ_ANIMATIONS", "Integer(1)"),
+            ("IMPORT_FBX_STRICT_MODE", "Integer(1)"),
+            ("IMPORT_FBX_PRESERVE_PIVOTS", "Integer(1)"),
+            ("IMPORT_FBX_OPTIMIZE_EMPTY_ANIMATION_CURVES", "Integer(1)"),
+        ]));
+
+        assert!(FbxOptions::default().ignored_extensions().is_empty());
+    }
+
+    #[test]
+    fn test_obj_options() {
+        let options = ObjOptions { smoothing_angle: 80.0, ignore_materials: true };
+
+        assert_eq!(applied(&options), expected(&[("PP_GSN_MAX_SMOOTHING_ANGLE", "Float(80.0)")]));
+        assert_eq!(options.ignored_extensions(), vec!["mtl"]);
+        assert!(ObjOptions::default().ignored_extensions().is_empty());
+    }
+
+    #[test]
+    fn test_collada_options() {
+        assert_eq!(applied(&ColladaOptions { ignore_up_direction: true, use_collada_names: false }), expected(&[
+            ("IMPORT_COLLADA_IGNORE_UP_DIRECTION", "Integer(1)"),
+            ("IMPORT_COLLADA_USE_COLLADA_NAMES", "Integer(0)"),
+        ]));
+    }
+
+    #[test]
+    fn test_gltf_options() {
+        assert_eq!(applied(&GltfOptions { no_skeleton_meshes: true, ..GltfOptions::default() }), expected(&[
+            ("IMPORT_NO_SKELETON_MESHES", "Integer(1)"),
+            ("AI_CONFIG_IMPORT_REMOVE_EMPTY_BONES", "Integer(1)"),
+        ]));
+    }
+}
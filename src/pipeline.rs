@@ -0,0 +1,217 @@
+//! Postprocessing pipelines mixing Assimp's built-in effects with custom steps
+
+use error::*;
+use postprocess::PostprocessEffect;
+use scene::Scene;
+
+/// A custom postprocessing step.
+///
+/// Steps borrow the scene mutably, so they can inspect it and store what they find, such as
+/// collision meshes, or change it in place through the unsafe `Scene::set_mesh_name` and `Scene::for_each_node_mut`.
+/// Since Assimp owns the scene's memory, steps can't add or remove anything, like meshes or metadata.
+/// Returning an error fails the pipeline, skipping the remaining stages.
+pub trait PostprocessStep {
+    /// Name of the step, used when reporting on the pipeline
+    fn name(&self) -> &str {
+        "custom step"
+    }
+
+    fn apply<'a>(&mut self, scene: &mut Scene<'a>) -> AiResult<()>;
+}
+
+impl<F> PostprocessStep for F where F: for<'a> FnMut(&mut Scene<'a>) -> AiResult<()> {
+    #[inline]
+    fn apply<'a>(&mut self, scene: &mut Scene<'a>) -> AiResult<()> {
+        (*self)(scene)
+    }
+}
+
+/// A single stage of a `Pipeline`
+pub enum Stage<'s> {
+    /// Built-in effects applied by Assimp in a single pass
+    Effects(PostprocessEffect),
+    Step(Box<PostprocessStep + 's>),
+}
+
+impl<'s> Stage<'s> {
+    /// Applies the stage to the scene
+    pub fn apply<'a>(&mut self, mut scene: Scene<'a>) -> AiResult<Scene<'a>> {
+        match *self {
+            Stage::Effects(effects) => scene.postprocess(effects),
+            Stage::Step(ref mut step) => {
+                try_rethrow!(step.apply(&mut scene));
+
+                Ok(scene)
+            }
+        }
+    }
+}
+
+/// Ordered list of built-in effects and custom steps to apply to a scene.
+///
+/// Assimp applies all effects passed to it at once in its own order, so every call to `effects`
+/// adds a separate pass, which runs after the stages before it.
+#[derive(Default)]
+pub struct Pipeline<'s> {
+    stages: Vec<Stage<'s>>,
+}
+
+impl<'s> Pipeline<'s> {
+    #[inline]
+    pub fn new() -> Pipeline<'s> {
+        Pipeline { stages: Vec::new() }
+    }
+
+    /// Add a pass of built-in effects
+    pub fn effects(mut self, effects: PostprocessEffect) -> Self {
+        self.stages.push(Stage::Effects(effects));
+
+        self
+    }
+
+    /// Add a custom step, which may borrow from its environment to store its results
+    pub fn step<S: PostprocessStep + 's>(mut self, step: S) -> Self {
+        self.stages.push(Stage::Step(Box::new(step)));
+
+        self
+    }
+
+    /// Add a closure as a custom step
+    pub fn step_fn<F>(self, f: F) -> Self where F: for<'a> FnMut(&mut Scene<'a>) -> AiResult<()> + 's {
+        self.step(f)
+    }
+
+    #[inline]
+    pub fn stages(&self) -> &[Stage<'s>] {
+        &self.stages
+    }
+
+    /// Get the stages for modification
+    #[inline]
+    pub fn stages_mut(&mut self) -> &mut [Stage<'s>] {
+        &mut self.stages
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Gets all built-in effects used by the pipeline
+    pub fn all_effects(&self) -> PostprocessEffect {
+        self.stages.iter().fold(PostprocessEffect::empty(), |all, stage| match *stage {
+            Stage::Effects(effects) => all | effects,
+            Stage::Step(_) => all,
+        })
+    }
+
+    /// Applies every stage to the scene in order, stopping at the first failure
+    pub fn run<'a>(&mut self, mut scene: Scene<'a>) -> AiResult<Scene<'a>> {
+        for stage in &mut self.stages {
+            scene = try_rethrow!(stage.apply(scene));
+        }
+
+        Ok(scene)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::mem;
+    use std::path::Path;
+    use std::ptr;
+
+    use ffi;
+
+    #[test]
+    fn test_steps_in_order() {
+        let mut child: ffi::AiNode = unsafe { mem::zeroed() };
+        let mut root: ffi::AiNode = unsafe { mem::zeroed() };
+        let mut raw_scene: ffi::AiScene = unsafe { mem::zeroed() };
+
+        child.name = ffi::AiString::from("child");
+        root.name = ffi::AiString::from("root");
+
+        let children = [&mut child as *mut ffi::AiNode as *const ffi::AiNode];
+
+        root.num_children = 1;
+        root.children = children.as_ptr();
+        raw_scene.root_node = &mut root as *mut ffi::AiNode;
+
+        let names = RefCell::new(Vec::new());
+
+        let scene = {
+            let mut pipeline = Pipeline::new()
+                .step_fn(|scene: &mut Scene| {
+                    unsafe {
+                        scene.for_each_node_mut(|node| {
+                            let name = format!("renamed {}", node.name());
+
+                            node.set_name(&name);
+                        });
+                    }
+
+                    Ok(())
+                })
+                .step_fn(|scene: &mut Scene| {
+                    unsafe {
+                        scene.for_each_node_mut(|node| names.borrow_mut().push(node.name().into_owned()));
+                    }
+
+                    Ok(())
+                });
+
+            assert_eq!(pipeline.len(), 2);
+            assert!(pipeline.all_effects().is_empty());
+
+            pipeline.run(Scene::from_import(&raw_scene, Path::new("test.obj"), Vec::new())).unwrap()
+        };
+
+        // The scene is owned by the test rather than Assimp
+        mem::forget(scene);
+
+        assert_eq!(*names.borrow(), vec!["renamed root", "renamed child"]);
+        assert_eq!(child.name.to_string_lossy(), "renamed child");
+    }
+
+    #[test]
+    fn test_step_error_stops_pipeline() {
+        let ran = RefCell::new(Vec::new());
+
+        let result = Pipeline::new()
+            .step_fn(|_: &mut Scene| {
+                ran.borrow_mut().push(1);
+
+                Ok(())
+            })
+            .step_fn(|_: &mut Scene| {
+                ran.borrow_mut().push(2);
+
+                throw!(AiError::Internal("rejected".to_string()))
+            })
+            .step_fn(|_: &mut Scene| {
+                ran.borrow_mut().push(3);
+
+                Ok(())
+            })
+            .run(Scene::from_import(ptr::null(), Path::new("test.obj"), Vec::new()));
+
+        match result {
+            Err(err) => match err.into_error() {
+                AiError::Internal(message) => assert_eq!(message, "rejected"),
+                err => panic!("unexpected error: {}", err),
+            },
+            Ok(_) => panic!("expected the error of the second step"),
+        }
+
+        assert_eq!(*ran.borrow(), vec![1, 2]);
+    }
+}
@@ -1,5 +1,10 @@
 use libc::c_uint;
 use ffi;
+use properties::*;
+use error::*;
+use library;
+
+pub use self::component::Component;
 
 bitflags! {
     /// Postprocess Effects bitflags
@@ -30,6 +35,242 @@ bitflags! {
         const FLIP_WINDING_ORDER = ffi::POSTPROCESS_FLIP_WINDING_ORDER,
         const SPLIT_BY_BONE_COUNT = ffi::POSTPROCESS_SPLIT_BY_BONE_COUNT,
         const DEBONE = ffi::POSTPROCESS_DEBONE,
+        const POPULATE_ARMATURE_DATA = ffi::POSTPROCESS_POPULATE_ARMATURE_DATA,
+        const GLOBAL_SCALE = ffi::POSTPROCESS_GLOBAL_SCALE,
+        const EMBED_TEXTURES = ffi::POSTPROCESS_EMBED_TEXTURES,
+        const FORCE_GEN_NORMALS = ffi::POSTPROCESS_FORCE_GEN_NORMALS,
+        const DROP_NORMALS = ffi::POSTPROCESS_DROP_NORMALS,
+        const GEN_BOUNDING_BOXES = ffi::POSTPROCESS_GEN_BOUNDING_BOXES,
+    }
+}
+
+/// Flags which only modify how other steps work, rather than being steps of their own
+pub const STEP_MODIFIERS: PostprocessEffect = FORCE_GEN_NORMALS;
+
+/// Every effect except `STEP_MODIFIERS` as a single step, approximately in the order Assimp applies them
+pub const STEP_ORDER: &'static [PostprocessEffect] = &[
+    VALIDATE_DATA_STRUCTURE,
+    DROP_NORMALS,
+    REMOVE_COMPONENT,
+    REMOVE_REDUNDANT_MATERIALS,
+    EMBED_TEXTURES,
+    FIND_INSTANCES,
+    OPTIMIZE_GRAPH,
+    OPTIMIZE_MESHES,
+    FIND_DEGENERATES,
+    GEN_UV_COORDS,
+    TRANSFORM_UV_COORDS,
+    GLOBAL_SCALE,
+    POPULATE_ARMATURE_DATA,
+    TRANSFORM_VERTICES,
+    TRIANGULATE,
+    SORT_BY_PTYPE,
+    FIND_INVALID_DATA,
+    FIX_INFACING_NORMALS,
+    SPLIT_BY_BONE_COUNT,
+    SPLIT_LARGE_MESHES,
+    GEN_NORMALS,
+    GEN_SMOOTH_NORMALS,
+    CALC_TANGENT_SPACE,
+    JOIN_IDENTICAL_VERTICES,
+    MAKE_LEFT_HANDED,
+    FLIP_UVS,
+    FLIP_WINDING_ORDER,
+    DEBONE,
+    LIMIT_BONE_WEIGHTS,
+    IMPROVE_CACHE_LOCALITY,
+    GEN_BOUNDING_BOXES,
+];
+
+/// Names of all effects, as used by `PostprocessEffect::from_name`
+const EFFECT_NAMES: &'static [(&'static str, PostprocessEffect)] = &[
+    ("CALC_TANGENT_SPACE", CALC_TANGENT_SPACE),
+    ("JOIN_IDENTICAL_VERTICES", JOIN_IDENTICAL_VERTICES),
+    ("MAKE_LEFT_HANDED", MAKE_LEFT_HANDED),
+    ("TRIANGULATE", TRIANGULATE),
+    ("REMOVE_COMPONENT", REMOVE_COMPONENT),
+    ("GEN_NORMALS", GEN_NORMALS),
+    ("GEN_SMOOTH_NORMALS", GEN_SMOOTH_NORMALS),
+    ("SPLIT_LARGE_MESHES", SPLIT_LARGE_MESHES),
+    ("TRANSFORM_VERTICES", TRANSFORM_VERTICES),
+    ("LIMIT_BONE_WEIGHTS", LIMIT_BONE_WEIGHTS),
+    ("VALIDATE_DATA_STRUCTURE", VALIDATE_DATA_STRUCTURE),
+    ("IMPROVE_CACHE_LOCALITY", IMPROVE_CACHE_LOCALITY),
+    ("REMOVE_REDUNDANT_MATERIALS", REMOVE_REDUNDANT_MATERIALS),
+    ("FIX_INFACING_NORMALS", FIX_INFACING_NORMALS),
+    ("SORT_BY_PTYPE", SORT_BY_PTYPE),
+    ("FIND_DEGENERATES", FIND_DEGENERATES),
+    ("FIND_INVALID_DATA", FIND_INVALID_DATA),
+    ("GEN_UV_COORDS", GEN_UV_COORDS),
+    ("TRANSFORM_UV_COORDS", TRANSFORM_UV_COORDS),
+    ("FIND_INSTANCES", FIND_INSTANCES),
+    ("OPTIMIZE_MESHES", OPTIMIZE_MESHES),
+    ("OPTIMIZE_GRAPH", OPTIMIZE_GRAPH),
+    ("FLIP_UVS", FLIP_UVS),
+    ("FLIP_WINDING_ORDER", FLIP_WINDING_ORDER),
+    ("SPLIT_BY_BONE_COUNT", SPLIT_BY_BONE_COUNT),
+    ("DEBONE", DEBONE),
+    ("POPULATE_ARMATURE_DATA", POPULATE_ARMATURE_DATA),
+    ("GLOBAL_SCALE", GLOBAL_SCALE),
+    ("EMBED_TEXTURES", EMBED_TEXTURES),
+    ("FORCE_GEN_NORMALS", FORCE_GEN_NORMALS),
+    ("DROP_NORMALS", DROP_NORMALS),
+    ("GEN_BOUNDING_BOXES", GEN_BOUNDING_BOXES),
+];
+
+/// How serious a problem found by `PostprocessEffect::validate` is
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IssueSeverity {
+    /// The effects work together, but likely not as expected
+    Warning,
+    /// Assimp refuses to apply the effects together
+    Conflict,
+}
+
+/// A problem with a combination of postprocess effects
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EffectIssue {
+    pub severity: IssueSeverity,
+    /// The effects involved
+    pub effects: PostprocessEffect,
+    pub message: &'static str,
+}
+
+impl EffectIssue {
+    #[inline]
+    fn new(severity: IssueSeverity, effects: PostprocessEffect, message: &'static str) -> EffectIssue {
+        EffectIssue { severity: severity, effects: effects, message: message }
+    }
+}
+
+impl PostprocessEffect {
+    /// Checks for effects which contradict each other or likely don't work as expected together
+    pub fn validate(&self) -> Vec<EffectIssue> {
+        let mut issues = Vec::new();
+
+        if self.contains(GEN_NORMALS | GEN_SMOOTH_NORMALS) {
+            issues.push(EffectIssue::new(IssueSeverity::Conflict, GEN_NORMALS | GEN_SMOOTH_NORMALS,
+                                         "GEN_NORMALS and GEN_SMOOTH_NORMALS cannot be used together"));
+        }
+
+        if self.contains(OPTIMIZE_GRAPH | TRANSFORM_VERTICES) {
+            issues.push(EffectIssue::new(IssueSeverity::Conflict, OPTIMIZE_GRAPH | TRANSFORM_VERTICES,
+                                         "OPTIMIZE_GRAPH and TRANSFORM_VERTICES cannot be used together"));
+        }
+
+        if self.contains(DEBONE | LIMIT_BONE_WEIGHTS) {
+            issues.push(EffectIssue::new(IssueSeverity::Warning, DEBONE | LIMIT_BONE_WEIGHTS,
+                                         "DEBONE may remove bones along with their weights, leaving fewer weights than LIMIT_BONE_WEIGHTS allows"));
+        }
+
+        if self.contains(FORCE_GEN_NORMALS) && !self.intersects(GEN_NORMALS | GEN_SMOOTH_NORMALS) {
+            issues.push(EffectIssue::new(IssueSeverity::Warning, FORCE_GEN_NORMALS,
+                                         "FORCE_GEN_NORMALS does nothing without GEN_NORMALS or GEN_SMOOTH_NORMALS"));
+        }
+
+        if self.contains(IMPROVE_CACHE_LOCALITY) && !self.contains(TRIANGULATE) {
+            issues.push(EffectIssue::new(IssueSeverity::Warning, IMPROVE_CACHE_LOCALITY,
+                                         "IMPROVE_CACHE_LOCALITY only optimizes triangles, so it has little effect without TRIANGULATE"));
+        }
+
+        issues
+    }
+
+    /// Gets the effects supported by the given version of Assimp.
+    ///
+    /// Older versions ignore effects they don't know about.
+    pub fn supported_by(major: u32, minor: u32) -> PostprocessEffect {
+        let version = (major, minor);
+
+        let mut effects = PostprocessEffect::all();
+
+        if version < (4, 1) {
+            effects.remove(GLOBAL_SCALE | EMBED_TEXTURES | FORCE_GEN_NORMALS);
+        }
+
+        if version < (5, 0) {
+            effects.remove(DROP_NORMALS | GEN_BOUNDING_BOXES);
+        }
+
+        if version < (5, 1) {
+            effects.remove(POPULATE_ARMATURE_DATA);
+        }
+
+        effects
+    }
+
+    /// Gets the effects supported by the linked Assimp library
+    pub fn supported() -> PostprocessEffect {
+        let info = library::version();
+
+        PostprocessEffect::supported_by(info.major, info.minor)
+    }
+
+    /// Gets the effects which the linked Assimp library would ignore
+    #[inline]
+    pub fn unsupported(&self) -> PostprocessEffect {
+        *self - PostprocessEffect::supported()
+    }
+
+    /// Looks up an effect or preset by the name of its constant, ignoring case,
+    /// such as `"TRIANGULATE"` or `"target_realtime_quality"`
+    pub fn from_name(name: &str) -> Option<PostprocessEffect> {
+        let name = name.trim().to_uppercase();
+
+        match name.as_str() {
+            "CONVERT_TO_LEFT_HANDED" => Some(*presets::CONVERT_TO_LEFT_HANDED),
+            "TARGET_REALTIME_FAST" => Some(*presets::TARGET_REALTIME_FAST),
+            "TARGET_REALTIME_QUALITY" => Some(*presets::TARGET_REALTIME_QUALITY),
+            "TARGET_REALTIME_MAX_QUALITY" => Some(*presets::TARGET_REALTIME_MAX_QUALITY),
+            _ => EFFECT_NAMES.iter().find(|entry| entry.0 == name).map(|entry| entry.1),
+        }
+    }
+
+    /// Gets the names of all effects, in the same form `from_name` accepts
+    pub fn names(&self) -> Vec<&'static str> {
+        EFFECT_NAMES.iter().filter(|entry| self.contains(entry.1)).map(|entry| entry.0).collect()
+    }
+
+    /// Splits the effects into single steps, ordered by `STEP_ORDER`.
+    ///
+    /// `FORCE_GEN_NORMALS` is kept with the normal generation step it modifies, and left out without one.
+    pub fn steps(&self) -> Vec<PostprocessEffect> {
+        STEP_ORDER.iter().cloned().filter(|step| self.contains(*step)).map(|step| {
+            if step.intersects(GEN_NORMALS | GEN_SMOOTH_NORMALS) {
+                step | (*self & FORCE_GEN_NORMALS)
+            } else {
+                step
+            }
+        }).collect()
+    }
+
+    /// Checks if there are any conflicting effects
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.validate().iter().all(|issue| issue.severity != IssueSeverity::Conflict)
+    }
+}
+
+/// Components which can be removed by the `REMOVE_COMPONENT` step
+pub mod component {
+    use libc::c_uint;
+    use ffi;
+
+    bitflags! {
+        /// Scene and mesh components bitflags
+        pub flags Component: c_uint {
+            const NORMALS = ffi::COMPONENT_NORMALS,
+            const TANGENTS_AND_BITANGENTS = ffi::COMPONENT_TANGENTS_AND_BITANGENTS,
+            const COLORS = ffi::COMPONENT_COLORS,
+            const TEXCOORDS = ffi::COMPONENT_TEXCOORDS,
+            const BONEWEIGHTS = ffi::COMPONENT_BONEWEIGHTS,
+            const ANIMATIONS = ffi::COMPONENT_ANIMATIONS,
+            const TEXTURES = ffi::COMPONENT_TEXTURES,
+            const LIGHTS = ffi::COMPONENT_LIGHTS,
+            const CAMERAS = ffi::COMPONENT_CAMERAS,
+            const MESHES = ffi::COMPONENT_MESHES,
+            const MATERIALS = ffi::COMPONENT_MATERIALS
+        }
     }
 }
 
@@ -54,7 +295,9 @@ pub mod presets {
 
 
 pub struct PostprocessEffectBuilder {
-    pub effects: PostprocessEffect
+    pub effects: PostprocessEffect,
+    /// Import properties configuring the enabled steps
+    pub properties: ImportProperties,
 }
 
 macro_rules! impl_builder_effect {
@@ -74,7 +317,8 @@ macro_rules! impl_builder_preset {
         #[inline(always)]
         pub fn $name() -> PostprocessEffectBuilder {
             PostprocessEffectBuilder {
-                effects: *presets::$preset
+                effects: *presets::$preset,
+                properties: ImportProperties::new(),
             }
         }
     }
@@ -89,12 +333,106 @@ impl Default for PostprocessEffectBuilder {
 impl PostprocessEffectBuilder {
     #[inline(always)]
     pub fn new() -> PostprocessEffectBuilder {
-        PostprocessEffectBuilder { effects: PostprocessEffect::empty() }
+        PostprocessEffectBuilder { effects: PostprocessEffect::empty(), properties: ImportProperties::new() }
     }
 
+    /// Builds the effects along with the import properties configuring them,
+    /// which should be set on the `Importer` used with the effects.
     #[inline(always)]
-    pub fn build(self) -> PostprocessEffect {
-        self.effects
+    pub fn build(self) -> (PostprocessEffect, ImportProperties) {
+        (self.effects, self.properties)
+    }
+
+    /// Checks the effects like `PostprocessEffect::validate`, as well as the step configuration
+    pub fn validate(&self) -> Vec<EffectIssue> {
+        let mut issues = self.effects.validate();
+
+        if self.effects.contains(REMOVE_COMPONENT) && self.properties.get(AI_CONFIG_PP_RVC_FLAGS).is_none() {
+            issues.push(EffectIssue::new(IssueSeverity::Warning, REMOVE_COMPONENT,
+                                         "REMOVE_COMPONENT does nothing unless the components are configured with `remove_component_with`"));
+        }
+
+        issues
+    }
+
+    /// Builds the effects and their import properties like `build`, failing with
+    /// `AiError::InvalidEffects` if any of the effects conflict.
+    ///
+    /// Warnings are ignored, use `validate` to find them.
+    pub fn build_checked(self) -> AiResult<(PostprocessEffect, ImportProperties)> {
+        let conflicts: Vec<EffectIssue> = self.validate().into_iter()
+                                              .filter(|issue| issue.severity == IssueSeverity::Conflict)
+                                              .collect();
+
+        if !conflicts.is_empty() {
+            throw!(AiError::InvalidEffects(conflicts));
+        }
+
+        Ok(self.build())
+    }
+
+    /// Enables `SPLIT_LARGE_MESHES`, splitting meshes with more vertices or triangles than the given limits
+    pub fn split_large_meshes_with(mut self, vertex_limit: u32, triangle_limit: u32) -> Self {
+        self.effects.insert(SPLIT_LARGE_MESHES);
+        self.properties.set_integer(AI_CONFIG_PP_SLM_VERTEX_LIMIT, vertex_limit as i32)
+                       .set_integer(AI_CONFIG_PP_SLM_TRIANGLE_LIMIT, triangle_limit as i32);
+
+        self
+    }
+
+    /// Enables `LIMIT_BONE_WEIGHTS`, keeping at most `max_weights` bone weights per vertex
+    pub fn limit_bone_weights_with(mut self, max_weights: u32) -> Self {
+        self.effects.insert(LIMIT_BONE_WEIGHTS);
+        self.properties.set_integer(AI_CONFIG_PP_LBW_MAX_WEIGHTS, max_weights as i32);
+
+        self
+    }
+
+    /// Enables `GEN_SMOOTH_NORMALS`, only smoothing normals less than `max_angle` degrees apart
+    pub fn gen_smooth_normals_with(mut self, max_angle: f32) -> Self {
+        self.effects.insert(GEN_SMOOTH_NORMALS);
+        self.properties.set_float(AI_CONFIG_PP_GSN_MAX_SMOOTHING_ANGLE, max_angle);
+
+        self
+    }
+
+    /// Enables `REMOVE_COMPONENT`, removing the given components from the scene
+    pub fn remove_component_with(mut self, components: Component) -> Self {
+        self.effects.insert(REMOVE_COMPONENT);
+        self.properties.set_integer(AI_CONFIG_PP_RVC_FLAGS, components.bits() as i32);
+
+        self
+    }
+
+    /// Enables `SPLIT_BY_BONE_COUNT`, splitting meshes with more than `max_bones` bones
+    pub fn split_by_bone_count_with(mut self, max_bones: u32) -> Self {
+        self.effects.insert(SPLIT_BY_BONE_COUNT);
+        self.properties.set_integer(AI_CONFIG_PP_SBBC_MAX_BONES, max_bones as i32);
+
+        self
+    }
+
+    /// Enables `GLOBAL_SCALE`, scaling the whole scene by `factor`
+    pub fn global_scale_with(mut self, factor: f32) -> Self {
+        self.effects.insert(GLOBAL_SCALE);
+        self.properties.set_float(AI_CONFIG_GLOBAL_SCALE_FACTOR_KEY, factor);
+
+        self
+    }
+
+    /// Enables `OPTIMIZE_GRAPH`, keeping the nodes with the given names
+    pub fn optimize_graph_with<I, S>(mut self, exclude: I) -> Self where I: IntoIterator<Item = S>, S: AsRef<str> {
+        // Names are separated by spaces, so quote any names containing them
+        let list: Vec<String> = exclude.into_iter().map(|name| {
+            let name = name.as_ref();
+
+            if name.contains(' ') { format!("'{}'", name) } else { name.to_string() }
+        }).collect();
+
+        self.effects.insert(OPTIMIZE_GRAPH);
+        self.properties.set_string(AI_CONFIG_PP_OG_EXCLUDE_LIST, list.join(" "));
+
+        self
     }
 
     impl_builder_preset!(CONVERT_TO_LEFT_HANDED, convert_to_left_handed);
@@ -128,4 +466,81 @@ impl PostprocessEffectBuilder {
     impl_builder_effect!(FLIP_WINDING_ORDER, flip_winding_order);
     impl_builder_effect!(SPLIT_BY_BONE_COUNT, split_by_bone_count);
     impl_builder_effect!(DEBONE, debone);
-}
\ No newline at end of file
+    impl_builder_effect!(POPULATE_ARMATURE_DATA, populate_armature_data);
+    impl_builder_effect!(GLOBAL_SCALE, global_scale);
+    impl_builder_effect!(EMBED_TEXTURES, embed_textures);
+    impl_builder_effect!(FORCE_GEN_NORMALS, force_gen_normals);
+    impl_builder_effect!(DROP_NORMALS, drop_normals);
+    impl_builder_effect!(GEN_BOUNDING_BOXES, gen_bounding_boxes);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_builder_properties() {
+        let (effects, properties) = PostprocessEffectBuilder::new()
+            .split_large_meshes_with(1000, 2000)
+            .optimize_graph_with(&["root", "left arm"])
+            .build();
+
+        assert_eq!(effects, SPLIT_LARGE_MESHES | OPTIMIZE_GRAPH);
+        assert!(effects.validate().is_empty());
+
+        match properties.get(AI_CONFIG_PP_SLM_TRIANGLE_LIMIT) {
+            Some(&PropertyValue::Integer(2000)) => {}
+            value => panic!("Unexpected triangle limit: {:?}", value),
+        }
+
+        match properties.get(AI_CONFIG_PP_OG_EXCLUDE_LIST) {
+            Some(&PropertyValue::String(ref list)) => assert_eq!(list, "root 'left arm'"),
+            value => panic!("Unexpected exclude list: {:?}", value),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let issues = (GEN_NORMALS | GEN_SMOOTH_NORMALS | IMPROVE_CACHE_LOCALITY).validate();
+
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].severity, IssueSeverity::Conflict);
+        assert_eq!(issues[1].severity, IssueSeverity::Warning);
+
+        assert!(IMPROVE_CACHE_LOCALITY.is_valid());
+
+        assert_eq!(STEP_ORDER.iter().fold(STEP_MODIFIERS, |all, step| all | *step), PostprocessEffect::all());
+        assert_eq!((FLIP_UVS | TRIANGULATE).steps(), vec![TRIANGULATE, FLIP_UVS]);
+
+        assert_eq!(PostprocessEffect::from_name("flip_uvs"), Some(FLIP_UVS));
+        assert_eq!(PostprocessEffect::from_name("convert_to_left_handed"), Some(*presets::CONVERT_TO_LEFT_HANDED));
+        assert_eq!(PostprocessEffect::from_name("FLIP"), None);
+        assert_eq!((FLIP_UVS | TRIANGULATE).names(), vec!["TRIANGULATE", "FLIP_UVS"]);
+
+        assert!(!PostprocessEffect::supported_by(4, 0).contains(GLOBAL_SCALE));
+        assert!(PostprocessEffect::supported_by(5, 0).contains(GEN_BOUNDING_BOXES));
+        assert!(!PostprocessEffect::supported_by(5, 0).contains(POPULATE_ARMATURE_DATA));
+        assert!(!(OPTIMIZE_GRAPH | TRANSFORM_VERTICES).is_valid());
+
+        assert!(PostprocessEffectBuilder::new().remove_component(true).build_checked().is_ok());
+        assert!(PostprocessEffectBuilder::new().gen_normals(true).gen_smooth_normals(true).build_checked().is_err());
+
+        let (effects, properties) = PostprocessEffectBuilder::new().limit_bone_weights_with(2).build_checked().unwrap();
+
+        assert_eq!(effects, LIMIT_BONE_WEIGHTS);
+        assert!(properties.get(AI_CONFIG_PP_LBW_MAX_WEIGHTS).is_some());
+    }
+
+    #[test]
+    fn test_step_modifiers() {
+        assert_eq!((FORCE_GEN_NORMALS | GEN_SMOOTH_NORMALS | TRIANGULATE).steps(),
+                   vec![TRIANGULATE, FORCE_GEN_NORMALS | GEN_SMOOTH_NORMALS]);
+        assert_eq!((FORCE_GEN_NORMALS | FLIP_UVS).steps(), vec![FLIP_UVS]);
+
+        for effects in &[PostprocessEffect::all(), FORCE_GEN_NORMALS, FORCE_GEN_NORMALS | GEN_NORMALS] {
+            for step in effects.steps() {
+                assert!(!(step - STEP_MODIFIERS).is_empty(), "{:?} is a lone modifier", step);
+            }
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Profiling of postprocessing, one step at a time
+
+use std::time::Duration;
+
+use postprocess::PostprocessEffect;
+
+/// Sizes of a scene, used to see how postprocessing changes it
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SceneStats {
+    pub meshes: usize,
+    pub vertices: usize,
+    pub faces: usize,
+    pub nodes: usize,
+}
+
+/// Difference between two `SceneStats`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SceneDelta {
+    pub meshes: isize,
+    pub vertices: isize,
+    pub faces: isize,
+    pub nodes: isize,
+}
+
+impl SceneStats {
+    /// Gets the change from `self` to `after`
+    pub fn delta(&self, after: &SceneStats) -> SceneDelta {
+        SceneDelta {
+            meshes: after.meshes as isize - self.meshes as isize,
+            vertices: after.vertices as isize - self.vertices as isize,
+            faces: after.faces as isize - self.faces as isize,
+            nodes: after.nodes as isize - self.nodes as isize,
+        }
+    }
+}
+
+/// Time taken and changes made by a single postprocessing step
+#[derive(Clone, Debug)]
+pub struct StepProfile {
+    pub effect: PostprocessEffect,
+    /// Wall time taken by Assimp to apply the step
+    pub time: Duration,
+    pub before: SceneStats,
+    pub after: SceneStats,
+}
+
+impl StepProfile {
+    #[inline]
+    pub fn delta(&self) -> SceneDelta {
+        self.before.delta(&self.after)
+    }
+}
+
+/// Report created by `Scene::postprocess_profiled`
+#[derive(Clone, Debug, Default)]
+pub struct PostprocessProfile {
+    /// Every step in the order it was applied
+    pub steps: Vec<StepProfile>,
+}
+
+impl PostprocessProfile {
+    /// Total time taken by all steps
+    pub fn total_time(&self) -> Duration {
+        self.steps.iter().fold(Duration::from_secs(0), |total, step| total + step.time)
+    }
+
+    /// Gets the steps sorted from slowest to fastest
+    pub fn slowest(&self) -> Vec<&StepProfile> {
+        let mut steps: Vec<&StepProfile> = self.steps.iter().collect();
+
+        steps.sort_by(|a, b| b.time.cmp(&a.time));
+
+        steps
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_delta() {
+        let before = SceneStats { meshes: 2, vertices: 100, faces: 40, nodes: 3 };
+        let after = SceneStats { meshes: 1, vertices: 120, faces: 40, nodes: 2 };
+
+        assert_eq!(before.delta(&after), SceneDelta { meshes: -1, vertices: 20, faces: 0, nodes: -1 });
+    }
+}
@@ -0,0 +1,194 @@
+//! Import properties, used to configure importers and postprocessing steps
+//!
+//! Property names are the `AI_CONFIG_*` keys from Assimp's `config.h`.
+
+use libc::c_int;
+
+use std::collections::BTreeMap;
+use std::collections::btree_map;
+use std::ffi::CString;
+
+use ffi::{self, AiMatrix4x4, AiReal, AiString};
+use error::*;
+
+/// Maximum number of vertices per mesh for `SPLIT_LARGE_MESHES`
+pub const AI_CONFIG_PP_SLM_VERTEX_LIMIT: &'static str = "PP_SLM_VERTEX_LIMIT";
+/// Maximum number of triangles per mesh for `SPLIT_LARGE_MESHES`
+pub const AI_CONFIG_PP_SLM_TRIANGLE_LIMIT: &'static str = "PP_SLM_TRIANGLE_LIMIT";
+/// Maximum number of bone weights per vertex for `LIMIT_BONE_WEIGHTS`
+pub const AI_CONFIG_PP_LBW_MAX_WEIGHTS: &'static str = "PP_LBW_MAX_WEIGHTS";
+/// Maximum angle between smoothed normals, in degrees, for `GEN_SMOOTH_NORMALS`
+pub const AI_CONFIG_PP_GSN_MAX_SMOOTHING_ANGLE: &'static str = "PP_GSN_MAX_SMOOTHING_ANGLE";
+/// Components removed by `REMOVE_COMPONENT`
+pub const AI_CONFIG_PP_RVC_FLAGS: &'static str = "PP_RVC_FLAGS";
+/// Maximum number of bones per mesh for `SPLIT_BY_BONE_COUNT`
+pub const AI_CONFIG_PP_SBBC_MAX_BONES: &'static str = "PP_SBBC_MAX_BONES";
+/// Names of nodes kept by `OPTIMIZE_GRAPH`
+pub const AI_CONFIG_PP_OG_EXCLUDE_LIST: &'static str = "PP_OG_EXCLUDE_LIST";
+/// Whether `TRANSFORM_VERTICES` applies `AI_CONFIG_PP_PTV_ROOT_TRANSFORMATION` to the scene
+pub const AI_CONFIG_PP_PTV_ADD_ROOT_TRANSFORMATION: &'static str = "PP_PTV_ADD_ROOT_TRANSFORMATION";
+/// Transformation applied to the whole scene by `TRANSFORM_VERTICES`
+pub const AI_CONFIG_PP_PTV_ROOT_TRANSFORMATION: &'static str = "PP_PTV_ROOT_TRANSFORMATION";
+/// Factor the scene is scaled by in `GLOBAL_SCALE`
+pub const AI_CONFIG_GLOBAL_SCALE_FACTOR_KEY: &'static str = "GLOBAL_SCALE_FACTOR";
+
+/// Don't generate dummy meshes to visualize the skeletons of files with bones but no meshes
+pub const AI_CONFIG_IMPORT_NO_SKELETON_MESHES: &'static str = "IMPORT_NO_SKELETON_MESHES";
+/// Remove bones without any vertex weights, which Assimp does by default
+pub const AI_CONFIG_IMPORT_REMOVE_EMPTY_BONES: &'static str = "AI_CONFIG_IMPORT_REMOVE_EMPTY_BONES";
+
+/// Read all geometry layers of FBX files, not just the first
+pub const AI_CONFIG_IMPORT_FBX_READ_ALL_GEOMETRY_LAYERS: &'static str = "IMPORT_FBX_READ_ALL_GEOMETRY_LAYERS";
+/// Read all materials of FBX files, even unused ones
+pub const AI_CONFIG_IMPORT_FBX_READ_ALL_MATERIALS: &'static str = "IMPORT_FBX_READ_ALL_MATERIALS";
+pub const AI_CONFIG_IMPORT_FBX_READ_MATERIALS: &'static str = "IMPORT_FBX_READ_MATERIALS";
+pub const AI_CONFIG_IMPORT_FBX_READ_TEXTURES: &'static str = "IMPORT_FBX_READ_TEXTURES";
+pub const AI_CONFIG_IMPORT_FBX_READ_CAMERAS: &'static str = "IMPORT_FBX_READ_CAMERAS";
+pub const AI_CONFIG_IMPORT_FBX_READ_LIGHTS: &'static str = "IMPORT_FBX_READ_LIGHTS";
+pub const AI_CONFIG_IMPORT_FBX_READ_ANIMATIONS: &'static str = "IMPORT_FBX_READ_ANIMATIONS";
+/// Fail on FBX files which don't follow the 2013 format strictly
+pub const AI_CONFIG_IMPORT_FBX_STRICT_MODE: &'static str = "IMPORT_FBX_STRICT_MODE";
+/// Keep FBX pivots as separate nodes instead of baking them into node transformations
+pub const AI_CONFIG_IMPORT_FBX_PRESERVE_PIVOTS: &'static str = "IMPORT_FBX_PRESERVE_PIVOTS";
+/// Drop FBX animation curves with constant values matching the bind pose
+pub const AI_CONFIG_IMPORT_FBX_OPTIMIZE_EMPTY_ANIMATION_CURVES: &'static str = "IMPORT_FBX_OPTIMIZE_EMPTY_ANIMATION_CURVES";
+/// Ignore the `<up_axis>` of Collada files instead of rotating the scene to be Y-up
+pub const AI_CONFIG_IMPORT_COLLADA_IGNORE_UP_DIRECTION: &'static str = "IMPORT_COLLADA_IGNORE_UP_DIRECTION";
+/// Name nodes by their Collada `id` instead of their `name`
+pub const AI_CONFIG_IMPORT_COLLADA_USE_COLLADA_NAMES: &'static str = "IMPORT_COLLADA_USE_COLLADA_NAMES";
+
+/// The value of a single import property
+#[derive(Clone, Debug)]
+pub enum PropertyValue {
+    Integer(i32),
+    Float(f32),
+    String(String),
+    Matrix(AiMatrix4x4),
+}
+
+/// A set of named import properties
+#[derive(Clone, Debug, Default)]
+pub struct ImportProperties {
+    properties: BTreeMap<String, PropertyValue>,
+}
+
+impl ImportProperties {
+    #[inline]
+    pub fn new() -> ImportProperties {
+        ImportProperties::default()
+    }
+
+    /// Sets a property, replacing any previous value with the same name
+    #[inline]
+    pub fn set<S: Into<String>>(&mut self, name: S, value: PropertyValue) -> &mut ImportProperties {
+        self.properties.insert(name.into(), value);
+
+        self
+    }
+
+    #[inline]
+    pub fn set_integer<S: Into<String>>(&mut self, name: S, value: i32) -> &mut ImportProperties {
+        self.set(name, PropertyValue::Integer(value))
+    }
+
+    /// Booleans are stored as integers by Assimp
+    #[inline]
+    pub fn set_bool<S: Into<String>>(&mut self, name: S, value: bool) -> &mut ImportProperties {
+        self.set(name, PropertyValue::Integer(value as i32))
+    }
+
+    #[inline]
+    pub fn set_float<S: Into<String>>(&mut self, name: S, value: f32) -> &mut ImportProperties {
+        self.set(name, PropertyValue::Float(value))
+    }
+
+    #[inline]
+    pub fn set_string<S: Into<String>, V: Into<String>>(&mut self, name: S, value: V) -> &mut ImportProperties {
+        self.set(name, PropertyValue::String(value.into()))
+    }
+
+    #[inline]
+    pub fn set_matrix<S: Into<String>>(&mut self, name: S, value: AiMatrix4x4) -> &mut ImportProperties {
+        self.set(name, PropertyValue::Matrix(value))
+    }
+
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&PropertyValue> {
+        self.properties.get(name)
+    }
+
+    #[inline]
+    pub fn remove(&mut self, name: &str) -> Option<PropertyValue> {
+        self.properties.remove(name)
+    }
+
+    /// Copies all properties from `other`, replacing any with the same name
+    pub fn extend(&mut self, other: &ImportProperties) {
+        for (name, value) in other.iter() {
+            self.properties.insert(name.clone(), value.clone());
+        }
+    }
+
+    #[inline]
+    pub fn iter(&self) -> btree_map::Iter<String, PropertyValue> {
+        self.properties.iter()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.properties.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+
+    /// Creates an Assimp property store containing all properties
+    pub fn to_store(&self) -> AiResult<PropertyStore> {
+        let store = PropertyStore { raw: unsafe { ffi::aiCreatePropertyStore() } };
+
+        for (name, value) in self.iter() {
+            let c_name = try_throw!(CString::new(name.as_str()));
+
+            unsafe {
+                match *value {
+                    PropertyValue::Integer(value) => {
+                        ffi::aiSetImportPropertyInteger(store.raw, c_name.as_ptr(), value as c_int)
+                    }
+                    PropertyValue::Float(value) => {
+                        ffi::aiSetImportPropertyFloat(store.raw, c_name.as_ptr(), value as AiReal)
+                    }
+                    PropertyValue::String(ref value) => {
+                        let value = AiString::from(value.as_str());
+
+                        ffi::aiSetImportPropertyString(store.raw, c_name.as_ptr(), &value)
+                    }
+                    PropertyValue::Matrix(ref value) => {
+                        ffi::aiSetImportPropertyMatrix(store.raw, c_name.as_ptr(), value)
+                    }
+                }
+            }
+        }
+
+        Ok(store)
+    }
+}
+
+/// Owned Assimp property store, released on drop
+pub struct PropertyStore {
+    raw: *mut ffi::AiPropertyStore,
+}
+
+impl PropertyStore {
+    #[inline]
+    pub fn as_ptr(&self) -> *const ffi::AiPropertyStore {
+        self.raw
+    }
+}
+
+impl Drop for PropertyStore {
+    fn drop(&mut self) {
+        unsafe { ffi::aiReleasePropertyStore(self.raw) }
+    }
+}
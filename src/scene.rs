@@ -1,18 +1,26 @@
 use libc::c_uint;
-use std::ffi::CString;
 use std::mem;
 use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
 use std::slice;
+use std::time::Instant;
 
 use ffi;
 use error::*;
 use postprocess::PostprocessEffect;
 use components::*;
+use logging::{LogCapture, ImportLog};
+use importer::Importer;
+use options::ImporterOptions;
+#[cfg(feature = "settings")]
+use settings::ImportSettings;
+use profile::{SceneStats, StepProfile, PostprocessProfile};
+use io::{CustomIO, DefaultIOHandler, ProgressIOHandler, Progress, ProgressAction};
 
 pub struct Scene<'a> {
     scene_ptr: *const ffi::AiScene,
     path: PathBuf,
+    validation_warnings: Vec<String>,
     _lifetime: PhantomData<&'a ()>
 }
 
@@ -24,13 +32,16 @@ bitflags! {
     pub flags SceneFlags: c_uint {
         const INCOMPLETE = ffi::SCENE_FLAG_INCOMPLETE,
         const VALIDATED = ffi::SCENE_FLAG_VALIDATED,
-        const VALIDATION_WARNIGN = ffi::SCENE_FLAG_VALIDATION_WARNING,
+        const VALIDATION_WARNING = ffi::SCENE_FLAG_VALIDATION_WARNING,
         const NON_VERBOSE_FORMAT = ffi::SCENE_FLAG_NON_VERBOSE_FORMAT,
         const TERRAIN = ffi::SCENE_FLAG_TERRAIN,
         const SHARED = ffi::SCENE_FLAG_ALLOW_SHARED
     }
 }
 
+#[deprecated(note = "Misspelled, use `VALIDATION_WARNING` instead")]
+pub const VALIDATION_WARNIGN: SceneFlags = VALIDATION_WARNING;
+
 macro_rules! impl_scene_iterator {
     ($field:ident, $num_field:ident, $t:ident) => {
         pub fn $field(&self) -> Option<Box<Iterator<Item = $t<'a>>>> {
@@ -75,85 +86,186 @@ impl<'a> Scene<'a> {
         self.path.as_path()
     }
 
-    pub fn import<P: AsRef<Path>>(path: P, effects: Option<PostprocessEffect>) -> AiResult<Scene<'a>> {
-        let path = path.as_ref();
-
-        let c_path = CString::new(path.to_str().unwrap()).unwrap();
-
-        let scene_ptr = unsafe {
-            ffi::aiImportFile(c_path.as_ptr(), match effects {
-                None => 0,
-                Some(flags) => flags.bits(),
-            })
-        };
+    /// Gets the individual warnings found by the `VALIDATE_DATA_STRUCTURE` step,
+    /// which are summarized by the `VALIDATION_WARNING` flag.
+    ///
+    /// Warnings are only captured if the step was requested when importing or postprocessing the scene.
+    #[inline]
+    pub fn validation_warnings(&self) -> &[String] {
+        &self.validation_warnings
+    }
 
-        let scene = Scene {
+    /// Wraps a scene pointer returned from an import, which may be invalid
+    pub(crate) fn from_import(scene_ptr: *const ffi::AiScene, path: &Path, validation_warnings: Vec<String>) -> Scene<'a> {
+        Scene {
             scene_ptr: scene_ptr,
             path: path.to_path_buf(),
+            validation_warnings: validation_warnings,
             _lifetime: PhantomData
-        };
+        }
+    }
 
-        if !scene.valid() {
-            check_assimp_errors!();
+    /// Import a scene from the filesystem.
+    ///
+    /// Equivalent to reading it with a new `Importer`.
+    pub fn import<P: AsRef<Path>>(path: P, effects: Option<PostprocessEffect>) -> AiResult<Scene<'a>> {
+        Importer::new().read_with(path, effects)
+    }
 
-            throw!(AiError::InvalidScene);
-        }
+    /// Import a scene with options for its importer, such as `FbxOptions`.
+    pub fn import_with_options<P: AsRef<Path>, O>(path: P, effects: Option<PostprocessEffect>, options: &O) -> AiResult<Scene<'a>>
+        where O: ImporterOptions {
+        let mut importer = Importer::new();
 
-        Ok(scene)
+        importer.set_options(options);
+        importer.read_with(path, effects)
+    }
+
+    /// Import a scene using the given custom IO system.
+    ///
+    /// If the import fails after a stream error occurred, that error is returned as `AiError::Io`.
+    ///
+    /// If any callback of the IO system panicked, the panic is resumed once Assimp has returned.
+    pub fn import_from<P: AsRef<Path>, IO>(path: P, effects: Option<PostprocessEffect>, io: &mut IO) -> AiResult<Scene<'a>> where IO: ::io::AssimpIO {
+        let mut importer = Importer::new();
+
+        importer.set_io(io);
+        importer.read_with(path, effects)
+    }
+
+    /// Import a scene, capturing all messages Assimp emits during the import.
+    ///
+    /// On failure, the captured messages are attached to the error's `ImportContext`, as returned by `AiError::log`,
+    /// so the error can still be matched directly.
+    pub fn import_with_log<P: AsRef<Path>>(path: P, effects: Option<PostprocessEffect>) -> AiResult<(Scene<'a>, ImportLog)> {
+        let mut importer = Importer::new();
+
+        match importer.read_with(path, effects) {
+            Ok(scene) => Ok((scene, importer.take_log())),
+            Err(err) => throw!(err.into_error().with_log(importer.take_log())),
+        }
     }
 
-    pub fn import_from<'b, P: AsRef<Path>, IO>(path: P, effects: Option<PostprocessEffect>, mut io: &'b mut IO) -> AiResult<Scene<'a>> where 'a: 'b, IO: ::io::AssimpIO<'b> {
+    /// Import a scene, applying the settings from its sidecar file, such as `hero.fbx.import.toml`, if it has one.
+    ///
+    /// The settings are returned along with the scene, since material overrides must be applied by the caller.
+    #[cfg(feature = "settings")]
+    pub fn import_with_settings<P: AsRef<Path>>(path: P) -> AiResult<(Scene<'a>, ImportSettings)> {
         let path = path.as_ref();
 
-        let c_path = CString::new(path.to_str().unwrap()).unwrap();
+        let settings = try_rethrow!(ImportSettings::find_for(path)).unwrap_or_default();
 
-        let scene_ptr = unsafe {
-            ffi::aiImportFileEx(c_path.as_ptr(), match effects {
-                None => 0,
-                Some(flags) => flags.bits(),
-            }, io.get())
-        };
+        let mut importer = Importer::new();
 
-        let scene = Scene {
-            scene_ptr: scene_ptr,
-            path: path.to_path_buf(),
-            _lifetime: PhantomData,
-        };
+        try_rethrow!(settings.apply(&mut importer));
 
-        if !scene.valid() {
-            check_assimp_errors!();
+        let scene = try_rethrow!(importer.read(path));
 
-            throw!(AiError::InvalidScene);
-        }
+        Ok((scene, settings))
+    }
 
-        Ok(scene)
+    /// Import a scene from the filesystem, reporting progress to the given callback.
+    ///
+    /// Progress is estimated from the number of bytes read. If the callback returns `ProgressAction::Cancel`,
+    /// the next read fails and `AiError::Cancelled` is returned.
+    pub fn import_with_progress<P: AsRef<Path>, F>(path: P, effects: Option<PostprocessEffect>, cb: F) -> AiResult<Scene<'a>>
+        where F: FnMut(Progress) -> ProgressAction + Send {
+        let path = path.as_ref();
+
+        let mut io = CustomIO::new(ProgressIOHandler::new(DefaultIOHandler, cb));
+
+        match Scene::import_from(path, effects, &mut io) {
+            Err(_) if io.handler().is_cancelled() => throw!(AiError::Cancelled(ImportContext::new(path, effects))),
+            res => res,
+        }
     }
 
     /// Apply postprocessing to the scene.
     ///
     /// Consumes the scene and returns a new one with the effects applied to it, that way
     /// no structure belonging to the original scene can maintain references to it.
-    pub fn postprocess<'b>(self, effects: PostprocessEffect) -> AiResult<Scene<'b>> where 'a: 'b {
+    pub fn postprocess<'b>(mut self, effects: PostprocessEffect) -> AiResult<Scene<'b>> where 'a: 'b {
+        let capture = LogCapture::start();
+
         let scene_ptr = unsafe {
             ffi::aiApplyPostProcessing(self.scene_ptr, effects.bits())
         };
 
+        let path = mem::replace(&mut self.path, PathBuf::new());
+        let mut validation_warnings = mem::replace(&mut self.validation_warnings, Vec::new());
+
+        // Assimp either returns the same scene or releases it and returns NULL on failure,
+        // so don't run the destructor on self either way.
+        mem::forget(self);
+
+        let log = capture.finish();
+
+        validation_warnings.extend(log.validation_warnings());
+
         let scene = Scene {
             scene_ptr: scene_ptr,
-            path: self.path.clone(),
+            path: path,
+            validation_warnings: validation_warnings,
             _lifetime: PhantomData
         };
 
         if !scene.valid() {
-            check_assimp_errors!();
+            throw!(AiError::postprocess_failed(ImportContext::new(&scene.path, Some(effects)), &log));
+        }
+
+        Ok(scene)
+    }
+
+    /// Apply postprocessing one step at a time, timing each step and recording how it changed the scene.
+    ///
+    /// Steps are applied in the order given by `postprocess::STEP_ORDER`, which approximates Assimp's own order.
+    /// Steps which share work when applied together, like `GEN_SMOOTH_NORMALS` and `JOIN_IDENTICAL_VERTICES`,
+    /// may take longer in total than with `postprocess`.
+    pub fn postprocess_profiled<'b>(self, effects: PostprocessEffect) -> AiResult<(Scene<'b>, PostprocessProfile)> where 'a: 'b {
+        let mut profile = PostprocessProfile::default();
+
+        let mut before = self.stats();
+        let mut scene: Scene<'b> = self;
+
+        for step in effects.steps() {
+            let start = Instant::now();
+
+            scene = try_rethrow!(scene.postprocess(step));
 
-            throw!(AiError::InvalidScene);
+            let time = start.elapsed();
+            let after = scene.stats();
+
+            profile.steps.push(StepProfile { effect: step, time: time, before: before, after: after });
+
+            before = after;
         }
 
-        //Don't run the destructor on self, since we took ownership of the pointer
-        mem::forget(self);
+        Ok((scene, profile))
+    }
 
-        Ok(scene)
+    /// Counts the meshes, vertices, faces and nodes in the scene.
+    ///
+    /// This is NOT zero-cost, as it has to walk the whole node hierarchy.
+    pub fn stats(&self) -> SceneStats {
+        fn count_nodes(node: &Node) -> usize {
+            1 + node.children().map_or(0, |children| children.map(|child| count_nodes(&child)).sum())
+        }
+
+        let mut stats = SceneStats::default();
+
+        if let Some(meshes) = self.meshes() {
+            for mesh in meshes {
+                stats.meshes += 1;
+                stats.vertices += mesh.num_vertices();
+                stats.faces += mesh.num_faces();
+            }
+        }
+
+        if !self.scene_ptr.is_null() && !self.raw_scene().root_node.is_null() {
+            stats.nodes = count_nodes(&self.root());
+        }
+
+        stats
     }
 
     impl_scene_iterator!(meshes, num_meshes, Mesh);
@@ -180,10 +292,63 @@ impl<'a> Scene<'a> {
 
         Node::from_raw(&scene.root_node)
     }
+
+    /// Renames the mesh at the given index, truncating the name to `MAXLEN - 1` bytes.
+    ///
+    /// Returns `false` if there is no such mesh.
+    ///
+    /// # Safety
+    ///
+    /// Components borrowed from the scene before, like `Mesh` and `Node`, aren't tied to this borrow
+    /// and must not be used while the name is written or afterwards.
+    pub unsafe fn set_mesh_name(&mut self, index: usize, name: &str) -> bool {
+        if self.scene_ptr.is_null() {
+            return false;
+        }
+
+        let scene: &ffi::AiScene = self.raw_scene();
+
+        if index >= scene.num_meshes as usize || scene.meshes.is_null() { false } else {
+            let mesh = *scene.meshes.offset(index as isize) as *mut ffi::AiMesh;
+
+            (*mesh).name = ffi::AiString::from(name);
+
+            true
+        }
+    }
+
+    /// Visits every node depth-first, starting at the root, with mutable access to its name and transformation.
+    ///
+    /// Only data stored inline in the scene's structures can be changed in place. Anything Assimp allocates
+    /// on its own, like node metadata or the node hierarchy itself, can't be resized or replaced from Rust.
+    ///
+    /// # Safety
+    ///
+    /// Components borrowed from the scene before, like `Node` and `Mesh`, aren't tied to this borrow
+    /// and must not be used while the nodes are visited or afterwards.
+    pub unsafe fn for_each_node_mut<F>(&mut self, mut f: F) where F: FnMut(&mut NodeMut) {
+        fn visit<F>(node: *mut ffi::AiNode, f: &mut F) where F: FnMut(&mut NodeMut) {
+            f(&mut NodeMut::from_raw_mut(unsafe { &mut *node }));
+
+            let node: &ffi::AiNode = unsafe { &*node };
+
+            if !node.children.is_null() {
+                for i in 0..node.num_children as isize {
+                    visit(unsafe { *node.children.offset(i) } as *mut ffi::AiNode, f);
+                }
+            }
+        }
+
+        if !self.scene_ptr.is_null() && !self.raw_scene().root_node.is_null() {
+            visit(self.raw_scene().root_node as *mut ffi::AiNode, &mut f);
+        }
+    }
 }
 
 impl<'a> Drop for Scene<'a> {
     fn drop(&mut self) {
-        unsafe { ffi::aiReleaseImport(self.scene_ptr) }
+        if !self.scene_ptr.is_null() {
+            unsafe { ffi::aiReleaseImport(self.scene_ptr) }
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,298 @@
+//! Per-asset import settings, loaded from TOML or JSON sidecar files
+//!
+//! The sidecar of `hero.fbx` is `hero.fbx.import.toml` or `hero.fbx.import.json`, such as:
+//!
+//! ```toml
+//! postprocess = ["target_realtime_fast", "flip_uvs"]
+//! scale = 0.01
+//!
+//! [axis]
+//! left_handed = true
+//! up = "z"
+//!
+//! [properties]
+//! PP_SLM_VERTEX_LIMIT = 10000
+//!
+//! [materials.Body]
+//! replace = "materials/skin.mat"
+//! textures = { diffuse = "textures/body_d.png" }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+
+use serde_json;
+use toml;
+
+use ffi::AiMatrix4x4;
+use error::*;
+use importer::Importer;
+use postprocess::*;
+use properties::*;
+
+/// Value of an import property in a settings file
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum SettingValue {
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+}
+
+/// Axis pointing up in the source asset
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpAxis {
+    Y,
+    Z,
+}
+
+/// Axis conversion applied to the asset
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AxisSettings {
+    /// Convert to a left-handed coordinate system, like `CONVERT_TO_LEFT_HANDED`
+    pub left_handed: bool,
+    /// Rotate Z-up assets to be Y-up.
+    ///
+    /// This uses `TRANSFORM_VERTICES`, which flattens the node hierarchy.
+    pub up: Option<UpAxis>,
+}
+
+/// Replacements for an imported material, which are applied by the caller when loading materials
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MaterialOverride {
+    /// Material to use instead of the imported one
+    pub replace: Option<String>,
+    /// Textures to use instead of the imported ones, by texture type such as `"diffuse"`
+    pub textures: BTreeMap<String, PathBuf>,
+}
+
+/// Import settings for a single asset
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ImportSettings {
+    /// Postprocess effects and presets by name, as accepted by `PostprocessEffect::from_name`
+    pub postprocess: Vec<String>,
+    /// Factor to scale the scene by, using `GLOBAL_SCALE`
+    pub scale: Option<f32>,
+    pub axis: AxisSettings,
+    /// Raw import properties, by their `AI_CONFIG_*` key
+    pub properties: BTreeMap<String, SettingValue>,
+    /// Material overrides, by material name
+    pub materials: BTreeMap<String, MaterialOverride>,
+}
+
+impl ImportSettings {
+    pub fn from_toml_str(s: &str) -> AiResult<ImportSettings> {
+        match toml::from_str(s) {
+            Ok(settings) => Ok(settings),
+            Err(err) => throw!(AiError::InvalidSettings(err.to_string())),
+        }
+    }
+
+    pub fn from_json_str(s: &str) -> AiResult<ImportSettings> {
+        match serde_json::from_str(s) {
+            Ok(settings) => Ok(settings),
+            Err(err) => throw!(AiError::InvalidSettings(err.to_string())),
+        }
+    }
+
+    /// Load settings from a file, which is parsed as JSON if it has a `json` extension and as TOML otherwise
+    pub fn load<P: AsRef<Path>>(path: P) -> AiResult<ImportSettings> {
+        let path = path.as_ref();
+
+        let mut contents = String::new();
+
+        try_throw!(try_throw!(File::open(path)).read_to_string(&mut contents));
+
+        let res = if path.extension().map_or(false, |ext| ext == "json") {
+            ImportSettings::from_json_str(&contents)
+        } else {
+            ImportSettings::from_toml_str(&contents)
+        };
+
+        match res {
+            Err(err) => match err.into_error() {
+                AiError::InvalidSettings(err) => throw!(AiError::InvalidSettings(format!("{}: {}", path.display(), err))),
+                err => throw!(err),
+            },
+            res => res,
+        }
+    }
+
+    /// Gets the possible sidecar files of an asset, in order of preference
+    pub fn sidecar_paths<P: AsRef<Path>>(asset: P) -> Vec<PathBuf> {
+        let asset = asset.as_ref().as_os_str();
+
+        [".import.toml", ".import.json"].iter().map(|suffix| {
+            let mut path = asset.to_os_string();
+
+            path.push(suffix);
+
+            PathBuf::from(path)
+        }).collect()
+    }
+
+    /// Load the sidecar settings of an asset, if it has any
+    pub fn find_for<P: AsRef<Path>>(asset: P) -> AiResult<Option<ImportSettings>> {
+        match ImportSettings::sidecar_paths(asset).into_iter().find(|path| path.is_file()) {
+            Some(path) => ImportSettings::load(path).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Gets the postprocess effects requested by the settings, including those needed for scale and axis conversion.
+    ///
+    /// Fails with `AiError::InvalidSettings` if any of the effects conflict, as found by `PostprocessEffect::validate`,
+    /// such as presets including `OPTIMIZE_GRAPH` along with `up = "z"`.
+    pub fn effects(&self) -> AiResult<PostprocessEffect> {
+        let mut effects = PostprocessEffect::empty();
+
+        for name in &self.postprocess {
+            match PostprocessEffect::from_name(name) {
+                Some(effect) => effects.insert(effect),
+                None => throw!(AiError::InvalidSettings(format!("Unknown postprocess effect \"{}\"", name))),
+            }
+        }
+
+        if self.scale.is_some() {
+            effects.insert(GLOBAL_SCALE);
+        }
+
+        if self.axis.left_handed {
+            effects.insert(*presets::CONVERT_TO_LEFT_HANDED);
+        }
+
+        if self.axis.up == Some(UpAxis::Z) {
+            effects.insert(TRANSFORM_VERTICES);
+        }
+
+        let conflicts: Vec<&'static str> = effects.validate().into_iter()
+                                                  .filter(|issue| issue.severity == IssueSeverity::Conflict)
+                                                  .map(|issue| issue.message)
+                                                  .collect();
+
+        if !conflicts.is_empty() {
+            throw!(AiError::InvalidSettings(format!("Conflicting postprocess effects: {}", conflicts.join("; "))));
+        }
+
+        Ok(effects)
+    }
+
+    /// Gets the import properties requested by the settings, including those needed for scale and axis conversion
+    pub fn import_properties(&self) -> AiResult<ImportProperties> {
+        let mut properties = ImportProperties::new();
+
+        for (name, value) in &self.properties {
+            match *value {
+                SettingValue::Bool(value) => properties.set_bool(name.as_str(), value),
+                SettingValue::Integer(value) if value >= i32::min_value() as i64 && value <= i32::max_value() as i64 => {
+                    properties.set_integer(name.as_str(), value as i32)
+                }
+                SettingValue::Integer(value) => {
+                    throw!(AiError::InvalidSettings(format!("Property \"{}\" is out of range: {}", name, value)))
+                }
+                SettingValue::Float(value) => properties.set_float(name.as_str(), value as f32),
+                SettingValue::String(ref value) => properties.set_string(name.as_str(), value.as_str()),
+            };
+        }
+
+        if let Some(scale) = self.scale {
+            properties.set_float(AI_CONFIG_GLOBAL_SCALE_FACTOR_KEY, scale);
+        }
+
+        if self.axis.up == Some(UpAxis::Z) {
+            // Rotates -90 degrees around the X axis, so +Z becomes +Y
+            properties.set_bool(AI_CONFIG_PP_PTV_ADD_ROOT_TRANSFORMATION, true)
+                      .set_matrix(AI_CONFIG_PP_PTV_ROOT_TRANSFORMATION, AiMatrix4x4 {
+                          a1: 1.0, a2: 0.0, a3: 0.0, a4: 0.0,
+                          b1: 0.0, b2: 0.0, b3: 1.0, b4: 0.0,
+                          c1: 0.0, c2: -1.0, c3: 0.0, c4: 0.0,
+                          d1: 0.0, d2: 0.0, d3: 0.0, d4: 1.0,
+                      });
+        }
+
+        Ok(properties)
+    }
+
+    /// Configure an importer with the effects and properties requested by the settings
+    pub fn apply(&self, importer: &mut Importer) -> AiResult<()> {
+        let effects = try_rethrow!(self.effects());
+        let properties = try_rethrow!(self.import_properties());
+
+        importer.properties_mut().extend(&properties);
+        importer.set_effects(if effects.is_empty() { None } else { Some(effects) });
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn material_override(&self, name: &str) -> Option<&MaterialOverride> {
+        self.materials.get(name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_settings() {
+        let settings = ImportSettings::from_toml_str(r#"
+            postprocess = ["triangulate", "FLIP_UVS"]
+            scale = 0.01
+
+            [axis]
+            up = "z"
+
+            [properties]
+            PP_SLM_VERTEX_LIMIT = 10000
+            PP_GSN_MAX_SMOOTHING_ANGLE = 80.0
+
+            [materials.Body]
+            textures = { diffuse = "body_d.png" }
+        "#).unwrap();
+
+        assert_eq!(settings.effects().unwrap(), TRIANGULATE | FLIP_UVS | GLOBAL_SCALE | TRANSFORM_VERTICES);
+        assert_eq!(settings.properties.get("PP_SLM_VERTEX_LIMIT"), Some(&SettingValue::Integer(10000)));
+        assert_eq!(settings.import_properties().unwrap().len(), 5);
+        assert_eq!(settings.material_override("Body").unwrap().textures.get("diffuse"), Some(&PathBuf::from("body_d.png")));
+
+        let json = ImportSettings::from_json_str(r#"{ "postprocess": ["flip_uvs"], "axis": { "left_handed": true } }"#).unwrap();
+
+        assert_eq!(json.effects().unwrap(), FLIP_UVS | *presets::CONVERT_TO_LEFT_HANDED);
+
+        assert!(ImportSettings::from_toml_str("postprocess = [\"flip\"]").unwrap().effects().is_err());
+        assert!(ImportSettings::from_toml_str("unknown = 1").is_err());
+
+        assert_eq!(ImportSettings::sidecar_paths("hero.fbx")[0], PathBuf::from("hero.fbx.import.toml"));
+    }
+
+    #[test]
+    fn test_conflicting_settings() {
+        let settings = ImportSettings::from_toml_str(r#"
+            postprocess = ["target_realtime_quality"]
+
+            [axis]
+            up = "z"
+        "#).unwrap();
+
+        match settings.effects() {
+            Err(err) => match err.into_error() {
+                AiError::InvalidSettings(ref message) => assert!(message.contains("TRANSFORM_VERTICES")),
+                err => panic!("Unexpected {:?}", err),
+            },
+            Ok(effects) => panic!("Unexpected {:?}", effects),
+        }
+
+        let mut importer = Importer::new();
+
+        assert!(settings.apply(&mut importer).is_err());
+        assert_eq!(importer.effects(), None);
+    }
+}